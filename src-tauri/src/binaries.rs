@@ -0,0 +1,223 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command;
+
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+/// 사용하는 옵션(`--download-sections`)을 지원하는 최소 yt-dlp 버전
+pub const MIN_YTDLP_VERSION: &str = "2022.06.22";
+/// `--js-runtimes` 옵션을 지원하는 yt-dlp 버전
+pub const JS_RUNTIMES_YTDLP_VERSION: &str = "2025.11.12";
+/// 버전 확인(스모크 테스트) 제한 시간
+const PROBE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 외부 실행 파일 위치 설정 (`{"mode": "custom", "path": "..."}`)
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "mode", content = "path", rename_all = "lowercase")]
+pub enum BinaryLocation {
+    /// 앱 폴더 → PATH 순으로 찾음
+    #[default]
+    Auto,
+    /// 앱이 받은 실행 파일만 사용
+    Bundled,
+    /// PATH에 설치된 실행 파일만 사용 (배포판 패키지 등)
+    System,
+    /// 지정한 절대 경로
+    Custom(String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Binary {
+    YtDlp,
+    Ffmpeg,
+    Deno,
+}
+
+impl Binary {
+    /// 앱 폴더/PATH에서 찾을 파일 이름
+    pub fn file_name(&self) -> &'static str {
+        match (self, cfg!(windows)) {
+            (Binary::YtDlp, true) => "yt-dlp.exe",
+            (Binary::YtDlp, false) => "yt-dlp",
+            (Binary::Ffmpeg, true) => "ffmpeg.exe",
+            (Binary::Ffmpeg, false) => "ffmpeg",
+            (Binary::Deno, true) => "deno.exe",
+            (Binary::Deno, false) => "deno",
+        }
+    }
+
+    fn version_arg(&self) -> &'static str {
+        match self {
+            Binary::Ffmpeg => "-version",
+            _ => "--version",
+        }
+    }
+
+    /// 버전 출력에서 버전 문자열만 추출
+    /// yt-dlp `2024.08.06`, ffmpeg `ffmpeg version 7.0.1 ...`, deno `deno 2.1.4 (...)`
+    fn parse_version(&self, stdout: &str) -> Option<String> {
+        let first_line = stdout.lines().next()?.trim();
+        let version = match self {
+            Binary::YtDlp => Some(first_line),
+            Binary::Ffmpeg => first_line
+                .strip_prefix("ffmpeg version ")?
+                .split_whitespace()
+                .next(),
+            Binary::Deno => first_line.strip_prefix("deno ")?.split_whitespace().next(),
+        };
+        version.filter(|v| !v.is_empty()).map(|v| v.to_string())
+    }
+
+    fn is_compatible(&self, version: &str) -> bool {
+        match self {
+            Binary::YtDlp => version_at_least(version, MIN_YTDLP_VERSION),
+            _ => true,
+        }
+    }
+}
+
+/// 감지된 실행 파일 상태
+#[derive(Clone, Debug, Serialize)]
+pub struct BinaryStatus {
+    pub binary: Binary,
+    pub location: BinaryLocation,
+    /// 찾은 실행 파일 (없으면 None)
+    pub path: Option<PathBuf>,
+    pub version: Option<String>,
+    /// 앱이 받은 실행 파일인지 (앱에서 업데이트 가능)
+    pub bundled: bool,
+    pub compatible: bool,
+    pub error: Option<String>,
+}
+
+impl BinaryStatus {
+    /// 실행 가능하고 호환되는 경우에만 경로 반환
+    pub fn usable_path(&self) -> Option<&Path> {
+        self.path.as_deref().filter(|_| self.compatible)
+    }
+}
+
+/// yt-dlp/ffmpeg/deno 감지 결과
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Binaries {
+    pub ytdlp: Option<BinaryStatus>,
+    pub ffmpeg: Option<BinaryStatus>,
+    pub deno: Option<BinaryStatus>,
+    /// 감지 시각 (unix seconds, 아직 감지하지 않았으면 None)
+    pub detected_at: Option<u64>,
+}
+
+/// 설정에 따라 실행 파일을 찾고 `--version`으로 실행해 확인
+///
+/// Auto는 앱 폴더의 파일을 먼저 보고, 없거나 호환되지 않으면 PATH에서 찾음
+pub async fn detect(binary: Binary, location: &BinaryLocation, data_dir: &Path) -> BinaryStatus {
+    let bundled = data_dir.join(binary.file_name());
+    let candidates: Vec<PathBuf> = match location {
+        BinaryLocation::Auto => std::iter::once(bundled.clone())
+            .chain(find_in_path(binary.file_name()))
+            .collect(),
+        BinaryLocation::Bundled => vec![bundled.clone()],
+        BinaryLocation::System => find_in_path(binary.file_name()).into_iter().collect(),
+        BinaryLocation::Custom(path) => vec![PathBuf::from(path.trim())],
+    };
+
+    let mut error = None;
+    for path in candidates {
+        if !path.is_absolute() || !path.is_file() {
+            error = Some(format!("{:?} not found", path));
+            continue;
+        }
+        let version = match probe_version(binary, &path).await {
+            Ok(version) => version,
+            Err(e) => {
+                error = Some(e);
+                continue;
+            }
+        };
+
+        let compatible = binary.is_compatible(&version);
+        let incompatible = (!compatible).then(|| {
+            format!(
+                "{:?} {} is older than the supported minimum {}",
+                path, version, MIN_YTDLP_VERSION
+            )
+        });
+        if *location == BinaryLocation::Auto && !compatible {
+            error = incompatible;
+            continue;
+        }
+        return BinaryStatus {
+            binary,
+            location: location.clone(),
+            bundled: path == bundled,
+            path: Some(path),
+            version: Some(version),
+            compatible,
+            error: incompatible,
+        };
+    }
+
+    BinaryStatus {
+        binary,
+        location: location.clone(),
+        path: None,
+        version: None,
+        bundled: false,
+        compatible: false,
+        error: Some(error.unwrap_or_else(|| format!("{} not found in PATH", binary.file_name()))),
+    }
+}
+
+/// 실행 파일을 버전 옵션으로 실행해 버전 확인 (스모크 테스트 겸용)
+pub async fn probe_version(binary: Binary, path: &Path) -> Result<String, String> {
+    let mut cmd = Command::new(path);
+    cmd.arg(binary.version_arg())
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    #[cfg(windows)]
+    {
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let output = tokio::time::timeout(PROBE_TIMEOUT, cmd.output())
+        .await
+        .map_err(|_| format!("{:?} {} timed out", path, binary.version_arg()))?
+        .map_err(|e| format!("{:?}: {}", path, e))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    match binary.parse_version(&stdout) {
+        Some(version) if output.status.success() => Ok(version),
+        _ => Err(format!(
+            "{:?} {} failed: {}",
+            path,
+            binary.version_arg(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )),
+    }
+}
+
+/// PATH에서 실행 파일 찾기
+fn find_in_path(file_name: &str) -> Option<PathBuf> {
+    let paths = std::env::var_os("PATH")?;
+    std::env::split_paths(&paths)
+        .map(|dir| dir.join(file_name))
+        .find(|path| path.is_absolute() && path.is_file())
+}
+
+/// 점으로 구분된 버전을 숫자 단위로 나눔 (`v2024.08.06` → [2024, 8, 6])
+pub fn version_parts(version: &str) -> Vec<u64> {
+    version
+        .trim_start_matches('v')
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
+
+pub fn version_at_least(version: &str, minimum: &str) -> bool {
+    version_parts(version) >= version_parts(minimum)
+}
@@ -0,0 +1,191 @@
+use futures::StreamExt;
+use reqwest::Client;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::broadcast;
+
+use crate::video_library::now_secs;
+
+/// 크기를 모를 때 진행 상황을 보내는 간격
+const UNKNOWN_SIZE_REPORT_BYTES: u64 = 1024 * 1024;
+
+/// 실행 파일/인스톨러 다운로드 단계
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BinaryDownloadState {
+    Downloading,
+    /// 받은 파일의 체크섬 확인 및 설치 중
+    Installing,
+    Completed,
+    Failed,
+}
+
+/// 실행 파일 다운로드 진행 상황 (`download-ytdlp-progress` 이벤트, /video/ytdlp/progress)
+#[derive(Clone, Debug, Serialize)]
+pub struct BinaryDownloadProgress {
+    /// 받는 파일 (릴리스 asset 이름)
+    pub asset: String,
+    pub state: BinaryDownloadState,
+    pub downloaded_bytes: u64,
+    /// Content-Length (없으면 None)
+    pub total_bytes: Option<u64>,
+    pub percent: Option<f32>,
+    pub error: Option<String>,
+    pub updated_at: u64,
+}
+
+impl BinaryDownloadProgress {
+    fn new(asset: &str, state: BinaryDownloadState) -> Self {
+        Self {
+            asset: asset.to_string(),
+            state,
+            downloaded_bytes: 0,
+            total_bytes: None,
+            percent: None,
+            error: None,
+            updated_at: now_secs(),
+        }
+    }
+}
+
+/// 실행 파일 다운로드 진행 상황을 기록하고 구독자에게 전달
+#[derive(Clone)]
+pub struct BinaryDownloads {
+    latest: Arc<std::sync::Mutex<Option<BinaryDownloadProgress>>>,
+    tx: broadcast::Sender<BinaryDownloadProgress>,
+}
+
+impl BinaryDownloads {
+    pub fn new() -> Self {
+        let (tx, _) = broadcast::channel(64);
+        Self {
+            latest: Arc::new(std::sync::Mutex::new(None)),
+            tx,
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<BinaryDownloadProgress> {
+        self.tx.subscribe()
+    }
+
+    /// 마지막으로 보고된 진행 상황
+    pub fn latest(&self) -> Option<BinaryDownloadProgress> {
+        self.latest.lock().ok().and_then(|latest| latest.clone())
+    }
+
+    fn report(&self, mut progress: BinaryDownloadProgress) {
+        progress.updated_at = now_secs();
+        if let Ok(mut latest) = self.latest.lock() {
+            *latest = Some(progress.clone());
+        }
+        let _ = self.tx.send(progress);
+    }
+
+    /// 다운로드 이후 단계(설치 중, 완료, 실패) 보고
+    pub fn set_state(&self, asset: &str, state: BinaryDownloadState, error: Option<String>) {
+        let mut progress = self
+            .latest()
+            .filter(|latest| latest.asset == asset)
+            .unwrap_or_else(|| BinaryDownloadProgress::new(asset, state));
+        progress.state = state;
+        progress.error = error;
+        self.report(progress);
+    }
+
+    /// 응답 본문을 파일에 나눠 쓰면서 SHA-256 계산 (메모리에 전체를 올리지 않음)
+    ///
+    /// 중간에 실패하면 파일을 지움. 받은 파일은 호출자가 확인한 뒤 최종 위치로 rename
+    pub async fn download_to_file(
+        &self,
+        client: &Client,
+        url: &str,
+        asset: &str,
+        path: &Path,
+    ) -> Result<String, String> {
+        let result = self.stream_to_file(client, url, asset, path).await;
+        if let Err(e) = &result {
+            let _ = tokio::fs::remove_file(path).await;
+            self.set_state(asset, BinaryDownloadState::Failed, Some(e.clone()));
+        }
+        result
+    }
+
+    async fn stream_to_file(
+        &self,
+        client: &Client,
+        url: &str,
+        asset: &str,
+        path: &Path,
+    ) -> Result<String, String> {
+        let mut progress = BinaryDownloadProgress::new(asset, BinaryDownloadState::Downloading);
+        self.report(progress.clone());
+
+        let response = client.get(url).send().await.map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "Failed to download {}: {}",
+                asset,
+                response.status()
+            ));
+        }
+        progress.total_bytes = response.content_length().filter(|total| *total > 0);
+
+        let mut file = tokio::fs::File::create(path)
+            .await
+            .map_err(|e| format!("Failed to create {:?}: {}", path, e))?;
+        let mut hasher = Sha256::new();
+        let mut stream = response.bytes_stream();
+        let mut last_reported = 0u64;
+
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.map_err(|e| format!("Download of {} interrupted: {}", asset, e))?;
+            file.write_all(&chunk)
+                .await
+                .map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+            hasher.update(&chunk);
+            progress.downloaded_bytes += chunk.len() as u64;
+
+            // 1% 또는 1MiB마다 보고
+            let changed = match progress.total_bytes {
+                Some(total) => {
+                    progress.downloaded_bytes * 100 / total != last_reported * 100 / total
+                }
+                None => progress.downloaded_bytes - last_reported >= UNKNOWN_SIZE_REPORT_BYTES,
+            };
+            if changed {
+                last_reported = progress.downloaded_bytes;
+                progress.percent = progress.total_bytes.map(|total| {
+                    (progress.downloaded_bytes as f64 / total as f64 * 100.0).min(100.0) as f32
+                });
+                self.report(progress.clone());
+            }
+        }
+
+        file.flush().await.map_err(|e| e.to_string())?;
+        file.sync_all().await.map_err(|e| e.to_string())?;
+        drop(file);
+
+        if let Some(total) = progress.total_bytes {
+            if progress.downloaded_bytes != total {
+                return Err(format!(
+                    "Download of {} ended early ({} of {} bytes)",
+                    asset, progress.downloaded_bytes, total
+                ));
+            }
+        }
+
+        progress.state = BinaryDownloadState::Installing;
+        progress.percent = Some(100.0);
+        self.report(progress);
+        Ok(format!("{:x}", hasher.finalize()))
+    }
+}
+
+impl Default for BinaryDownloads {
+    fn default() -> Self {
+        Self::new()
+    }
+}
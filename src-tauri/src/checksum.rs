@@ -0,0 +1,164 @@
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+/// 릴리스 체크섬 파일 이름 (yt-dlp, 오프라인 번들)
+pub const SUMS_FILE_NAME: &str = "SHA2-256SUMS";
+
+/// GitHub 릴리스 asset
+#[derive(Clone, Debug, Deserialize)]
+pub struct ReleaseAsset {
+    pub name: String,
+    pub browser_download_url: String,
+    /// GitHub가 계산한 해시 (`sha256:<hex>`, 오래된 릴리스에는 없음)
+    #[serde(default)]
+    pub digest: Option<String>,
+}
+
+/// 체크섬 확인 결과
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationStatus {
+    Verified,
+    Mismatch,
+    /// 릴리스에 체크섬이 없어 확인할 수 없음
+    Unavailable,
+}
+
+/// 기대 해시를 어디서 얻었는지
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChecksumSource {
+    /// GitHub API asset의 digest
+    AssetDigest,
+    /// 릴리스에 함께 올라온 체크섬 파일 (SHA2-256SUMS, *.sha256sum)
+    ChecksumFile,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Verification {
+    pub status: VerificationStatus,
+    pub asset: String,
+    /// 받은 파일의 SHA-256
+    pub sha256: String,
+    pub expected: Option<String>,
+    pub checksum_source: Option<ChecksumSource>,
+}
+
+impl Verification {
+    /// 확인된 파일만 통과 (불일치/확인 불가면 설치 거부)
+    pub fn ensure_verified(&self) -> Result<(), String> {
+        match self.status {
+            VerificationStatus::Verified => Ok(()),
+            VerificationStatus::Mismatch => Err(format!(
+                "Checksum mismatch for {} (expected {}, got {})",
+                self.asset,
+                self.expected.as_deref().unwrap_or_default(),
+                self.sha256
+            )),
+            VerificationStatus::Unavailable => {
+                Err(format!("No published checksum for {}", self.asset))
+            }
+        }
+    }
+}
+
+/// 받은 파일의 SHA-256을 릴리스에 공개된 값과 비교
+///
+/// asset digest가 있으면 사용하고, 없으면 SHA2-256SUMS나 `{asset}.sha256sum` 파일에서 찾음
+pub async fn verify_asset(
+    client: &Client,
+    assets: &[ReleaseAsset],
+    asset: &ReleaseAsset,
+    sha256: &str,
+) -> Verification {
+    let expected = expected_sha256(client, assets, asset).await;
+    compare(&asset.name, sha256.to_string(), expected)
+}
+
+/// 함께 받은 SHA2-256SUMS 내용으로 확인 (오프라인 번들)
+pub fn verify_with_sums(sums: Option<&str>, file_name: &str, sha256: &str) -> Verification {
+    let expected = sums
+        .and_then(|text| find_in_sums(text, file_name))
+        .map(|hash| (hash, ChecksumSource::ChecksumFile));
+    compare(file_name, sha256.to_string(), expected)
+}
+
+fn compare(name: &str, sha256: String, expected: Option<(String, ChecksumSource)>) -> Verification {
+    let status = match &expected {
+        Some((expected, _)) if *expected == sha256 => VerificationStatus::Verified,
+        Some(_) => VerificationStatus::Mismatch,
+        None => VerificationStatus::Unavailable,
+    };
+
+    if status == VerificationStatus::Verified {
+        tracing::info!("Verified SHA-256 of {}", name);
+    } else {
+        tracing::warn!("Checksum verification of {} failed: {:?}", name, status);
+    }
+    Verification {
+        status,
+        asset: name.to_string(),
+        sha256,
+        expected: expected.as_ref().map(|(hash, _)| hash.clone()),
+        checksum_source: expected.map(|(_, source)| source),
+    }
+}
+
+async fn expected_sha256(
+    client: &Client,
+    assets: &[ReleaseAsset],
+    asset: &ReleaseAsset,
+) -> Option<(String, ChecksumSource)> {
+    if let Some(hash) = asset
+        .digest
+        .as_deref()
+        .and_then(|digest| digest.strip_prefix("sha256:"))
+        .filter(|hash| is_sha256_hex(hash))
+    {
+        return Some((hash.to_ascii_lowercase(), ChecksumSource::AssetDigest));
+    }
+
+    let per_asset_name = format!("{}.sha256sum", asset.name);
+    for sums in assets
+        .iter()
+        .filter(|a| a.name == SUMS_FILE_NAME || a.name == per_asset_name)
+    {
+        let Some(text) = fetch_text(client, &sums.browser_download_url).await else {
+            continue;
+        };
+        let hash = if sums.name == SUMS_FILE_NAME {
+            find_in_sums(&text, &asset.name)
+        } else {
+            // 단일 파일용 체크섬은 형식이 제각각이라 (sha256sum, Get-FileHash) 첫 해시 사용
+            text.split_whitespace()
+                .find(|token| is_sha256_hex(token))
+                .map(|hash| hash.to_ascii_lowercase())
+        };
+        if let Some(hash) = hash {
+            return Some((hash, ChecksumSource::ChecksumFile));
+        }
+    }
+    None
+}
+
+/// `sha256sum` 형식 (`<hash>  <name>` 또는 `<hash> *<name>`)에서 해당 파일의 해시
+fn find_in_sums(text: &str, file_name: &str) -> Option<String> {
+    text.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        let hash = parts.next()?;
+        let name = parts.next()?.trim_start_matches('*');
+        (name == file_name && is_sha256_hex(hash)).then(|| hash.to_ascii_lowercase())
+    })
+}
+
+fn is_sha256_hex(text: &str) -> bool {
+    text.len() == 64 && text.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+async fn fetch_text(client: &Client, url: &str) -> Option<String> {
+    let response = client.get(url).send().await.ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    response.text().await.ok()
+}
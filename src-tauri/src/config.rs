@@ -1,6 +1,9 @@
 use std::fs;
 use std::path::PathBuf;
 
+use crate::binaries::BinaryLocation;
+use crate::network::IpFamily;
+
 /// 앱 설정 (JavaScript와 호환을 위해 camelCase 사용)
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 #[allow(non_snake_case)]
@@ -20,16 +23,88 @@ pub struct AppConfig {
     /// cookies.txt 파일 경로 (YouTube 성인인증 영상에 필요)
     #[serde(default)]
     pub cookiesFile: String,
+    /// 쿠키를 가져올 브라우저 (chrome, firefox 등, 비어 있으면 지정하지 않음)
+    #[serde(default)]
+    pub cookiesBrowser: String,
+    /// 브라우저 프로필 이름 또는 경로 (비어 있으면 기본 프로필)
+    #[serde(default)]
+    pub cookiesBrowserProfile: String,
+    /// Firefox 컨테이너 이름 (비어 있으면 컨테이너 밖의 쿠키)
+    #[serde(default)]
+    pub cookiesBrowserContainer: String,
+    /// 지정한 쿠키로 안 되면 설치된 다른 브라우저도 차례로 시도
+    #[serde(default = "default_true")]
+    pub cookiesAutoProbe: bool,
+    /// 캐시 정리에서 제외할 비디오 id 목록
+    #[serde(default)]
+    pub pinnedVideos: Vec<String>,
+    /// 마지막 재생 후 이 기간(일)이 지나면 삭제 (0이면 사용 안 함)
+    #[serde(default)]
+    pub maxCacheAgeDays: u32,
+    /// 트랙이 바뀌면 이전에 요청했던 영상을 자동으로 미리 다운로드
+    #[serde(default = "default_true")]
+    pub videoPrefetch: bool,
+    /// 새 yt-dlp 릴리스가 있으면 자동으로 업데이트
+    #[serde(default = "default_true")]
+    pub ytdlpAutoUpdate: bool,
+    /// yt-dlp 업데이트 확인 간격 (시간)
+    #[serde(default = "default_update_interval")]
+    pub ytdlpUpdateIntervalHours: u32,
+    /// yt-dlp 위치 (auto: 앱 폴더 → PATH, bundled, system, custom)
+    #[serde(default)]
+    pub ytdlpLocation: BinaryLocation,
+    /// ffmpeg 위치 (병합/구간 다운로드, 썸네일 생성)
+    #[serde(default)]
+    pub ffmpegLocation: BinaryLocation,
+    /// deno 위치 (YouTube JS 해석)
+    #[serde(default)]
+    pub denoLocation: BinaryLocation,
+    /// GitHub API 호환 미러 주소 (비어 있으면 https://api.github.com)
+    /// `{주소}/repos/yt-dlp/yt-dlp/releases/latest` 형식으로 조회
+    #[serde(default)]
+    pub releaseApiBaseUrl: String,
+    /// 릴리스 파일 다운로드 미러 주소 (비어 있으면 API가 알려준 주소 그대로)
+    /// `https://github.com`으로 시작하는 다운로드 주소의 앞부분을 이 주소로 바꿈
+    #[serde(default)]
+    pub releaseDownloadBaseUrl: String,
+    /// HTTP/SOCKS 프록시 (예: http://proxy:8080, socks5://127.0.0.1:1080)
+    #[serde(default)]
+    pub proxy: String,
+    /// yt-dlp 다운로드 속도 제한 (예: 500K, 4.2M, 비어 있으면 제한 없음)
+    #[serde(default)]
+    pub limitRate: String,
+    /// 연결에 사용할 로컬 IP 주소
+    #[serde(default)]
+    pub sourceAddress: String,
+    /// IPv4/IPv6 강제 (any, ipv4, ipv6)
+    #[serde(default)]
+    pub ipFamily: IpFamily,
+    /// 사용자 지정 User-Agent (비어 있으면 기본값)
+    #[serde(default)]
+    pub userAgent: String,
 }
 
 fn default_max_cache() -> u32 {
     10
 }
 
+fn default_true() -> bool {
+    true
+}
+
+fn default_update_interval() -> u32 {
+    24
+}
+
 fn default_language() -> String {
     "en".to_string()
 }
 
+/// 앞뒤 공백을 뺀 설정 값 (비어 있으면 None)
+pub(crate) fn non_empty(value: &str) -> Option<&str> {
+    Some(value.trim()).filter(|value| !value.is_empty())
+}
+
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
@@ -40,6 +115,25 @@ impl Default for AppConfig {
             startOnBoot: false,
             language: "en".to_string(),
             cookiesFile: String::new(),
+            cookiesBrowser: String::new(),
+            cookiesBrowserProfile: String::new(),
+            cookiesBrowserContainer: String::new(),
+            cookiesAutoProbe: true,
+            pinnedVideos: Vec::new(),
+            maxCacheAgeDays: 0,
+            videoPrefetch: true,
+            ytdlpAutoUpdate: true,
+            ytdlpUpdateIntervalHours: 24,
+            ytdlpLocation: BinaryLocation::Auto,
+            ffmpegLocation: BinaryLocation::Auto,
+            denoLocation: BinaryLocation::Auto,
+            releaseApiBaseUrl: String::new(),
+            releaseDownloadBaseUrl: String::new(),
+            proxy: String::new(),
+            limitRate: String::new(),
+            sourceAddress: String::new(),
+            ipFamily: IpFamily::Any,
+            userAgent: String::new(),
         }
     }
 }
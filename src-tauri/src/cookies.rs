@@ -0,0 +1,209 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::config::{non_empty, AppConfig};
+use crate::video_library::now_secs;
+
+/// yt-dlp `--cookies-from-browser`가 지원하는 브라우저
+const SUPPORTED_BROWSERS: [&str; 9] = [
+    "brave", "chrome", "chromium", "edge", "firefox", "opera", "safari", "vivaldi", "whale",
+];
+
+/// 성인인증 등으로 다시 시도할 때 사용할 쿠키 출처
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CookieSource {
+    /// cookies.txt 파일
+    File { path: String },
+    /// 브라우저 쿠키 (yt-dlp `browser:profile::container` 형식)
+    Browser { spec: String },
+}
+
+impl CookieSource {
+    /// yt-dlp에 전달할 인자
+    pub fn ytdlp_args(&self) -> [String; 2] {
+        match self {
+            Self::File { path } => ["--cookies".to_string(), path.clone()],
+            Self::Browser { spec } => ["--cookies-from-browser".to_string(), spec.clone()],
+        }
+    }
+
+    /// 진행 메시지에 표시할 이름
+    pub fn label(&self) -> &str {
+        match self {
+            Self::File { .. } => "cookies.txt",
+            Self::Browser { spec } => spec,
+        }
+    }
+}
+
+/// 쿠키 설정 확인 (설정 저장 전에 호출)
+pub fn validate(config: &AppConfig) -> Result<(), String> {
+    let browser = config.cookiesBrowser.trim().to_lowercase();
+    let profile = config.cookiesBrowserProfile.trim();
+    let container = config.cookiesBrowserContainer.trim();
+
+    if browser.is_empty() {
+        if !profile.is_empty() || !container.is_empty() {
+            return Err("Choose a browser for the cookie profile or container".to_string());
+        }
+        return Ok(());
+    }
+    if !SUPPORTED_BROWSERS.contains(&browser.as_str()) {
+        return Err(format!(
+            "Unsupported cookie browser {:?} (expected one of {})",
+            config.cookiesBrowser,
+            SUPPORTED_BROWSERS.join(", ")
+        ));
+    }
+    if !container.is_empty() && browser != "firefox" {
+        return Err("Cookie containers are only supported for Firefox".to_string());
+    }
+    // `::`는 컨테이너 구분자라 프로필 이름/경로에 들어가면 잘못 해석됨
+    if profile.contains("::")
+        || [profile, container]
+            .iter()
+            .any(|s| s.chars().any(char::is_control))
+    {
+        return Err(format!("Invalid cookie browser profile {:?}", profile));
+    }
+    Ok(())
+}
+
+/// 설정에서 고른 브라우저 (`browser:profile::container`, 고르지 않았으면 None)
+fn configured_browser(config: &AppConfig) -> Option<String> {
+    let browser = non_empty(&config.cookiesBrowser)?.to_lowercase();
+    let mut spec = browser;
+    if let Some(profile) = non_empty(&config.cookiesBrowserProfile) {
+        spec.push(':');
+        spec.push_str(profile);
+    }
+    if let Some(container) = non_empty(&config.cookiesBrowserContainer) {
+        spec.push_str("::");
+        spec.push_str(container);
+    }
+    Some(spec)
+}
+
+/// 시도할 쿠키 출처 순서
+///
+/// cookies.txt → 설정한 브라우저 → 감지된 브라우저(자동 탐색이 켜져 있을 때) 순서이며,
+/// 마지막으로 성공한 출처가 아직 설정에서 허용되면 맨 앞으로 옮김
+pub fn candidates(
+    config: &AppConfig,
+    installed_browsers: &[&str],
+    last_working: Option<&CookieSource>,
+) -> Vec<CookieSource> {
+    let mut sources = Vec::new();
+
+    if let Some(path) = non_empty(&config.cookiesFile).filter(|path| Path::new(path).exists()) {
+        sources.push(CookieSource::File {
+            path: path.to_string(),
+        });
+    }
+    if let Some(spec) = configured_browser(config) {
+        sources.push(CookieSource::Browser { spec });
+    }
+    if config.cookiesAutoProbe {
+        let configured = config.cookiesBrowser.trim().to_lowercase();
+        for browser in installed_browsers.iter().filter(|b| **b != configured) {
+            sources.push(CookieSource::Browser {
+                spec: browser.to_string(),
+            });
+        }
+    }
+
+    if let Some(last) = last_working {
+        if let Some(index) = sources.iter().position(|source| source == last) {
+            let source = sources.remove(index);
+            sources.insert(0, source);
+        }
+    }
+    sources
+}
+
+/// 저장된 쿠키 상태
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CookieState {
+    /// 마지막으로 다운로드에 성공한 쿠키 출처
+    #[serde(default)]
+    pub last_working: Option<CookieSource>,
+    /// 마지막 갱신 시각 (unix seconds)
+    #[serde(default)]
+    pub updated_at: u64,
+}
+
+/// 마지막으로 성공한 쿠키 출처 기록 (data dir의 cookie_state.json에 저장)
+#[derive(Clone)]
+pub struct CookieStateStore {
+    path: PathBuf,
+    state: Arc<Mutex<CookieState>>,
+}
+
+impl CookieStateStore {
+    pub fn new(data_dir: &Path) -> Self {
+        let path = data_dir.join("cookie_state.json");
+        let state = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        Self {
+            path,
+            state: Arc::new(Mutex::new(state)),
+        }
+    }
+
+    pub fn get(&self) -> CookieState {
+        self.state
+            .lock()
+            .map(|state| state.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn last_working(&self) -> Option<CookieSource> {
+        self.get().last_working
+    }
+
+    /// 다운로드에 성공한 출처 기록
+    pub fn remember(&self, source: &CookieSource) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        if state.last_working.as_ref() == Some(source) {
+            return;
+        }
+        state.last_working = Some(source.clone());
+        state.updated_at = now_secs();
+        self.save(&state);
+    }
+
+    /// 기록된 출처가 더 이상 동작하지 않으면 삭제
+    pub fn forget(&self, source: &CookieSource) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        if state.last_working.as_ref() != Some(source) {
+            return;
+        }
+        state.last_working = None;
+        state.updated_at = now_secs();
+        self.save(&state);
+    }
+
+    fn save(&self, state: &CookieState) {
+        if let Some(parent) = self.path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        match serde_json::to_string_pretty(state) {
+            Ok(content) => {
+                if let Err(e) = fs::write(&self.path, content) {
+                    tracing::warn!("Failed to save cookie state: {}", e);
+                }
+            }
+            Err(e) => tracing::warn!("Failed to serialize cookie state: {}", e),
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use serde::Serialize;
+use std::fmt;
+use std::time::Duration;
+
+/// 다운로드 실패 원인 (클라이언트가 안내 문구를 고를 수 있도록 error_code로 전달)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadErrorCode {
+    /// 성인인증 필요 (쿠키로 재시도 가능)
+    AgeRestricted,
+    /// 비공개 또는 멤버십 전용
+    Private,
+    /// 삭제되었거나 존재하지 않는 영상, 지원하지 않는 URL
+    Unavailable,
+    /// 요청한 모드/화질의 포맷이 없음 (다른 모드로는 받을 수 있음)
+    FormatUnavailable,
+    /// 현재 지역에서 볼 수 없음
+    GeoBlocked,
+    /// 요청이 너무 많음 (HTTP 429, 봇 확인)
+    RateLimited,
+    /// 브라우저 쿠키를 읽지 못함 (DPAPI, 쿠키 DB 잠김)
+    CookieExtractionFailed,
+    /// yt-dlp 또는 ffmpeg 실행 파일 없음
+    BinaryMissing,
+    /// 연결 실패, 시간 초과, 프록시 오류
+    Network,
+    Unknown,
+}
+
+impl DownloadErrorCode {
+    /// 같은 영상을 다시 시도하기 전까지 실패를 기억할 시간
+    /// (네트워크 오류처럼 일시적이거나 설정으로 해결되는 원인은 기억하지 않음)
+    pub fn negative_cache_ttl(&self) -> Option<Duration> {
+        let minutes = match self {
+            Self::Unavailable | Self::GeoBlocked => 24 * 60,
+            Self::Private => 6 * 60,
+            Self::AgeRestricted => 60,
+            Self::RateLimited => 15,
+            Self::FormatUnavailable
+            | Self::CookieExtractionFailed
+            | Self::BinaryMissing
+            | Self::Network
+            | Self::Unknown => return None,
+        };
+        Some(Duration::from_secs(minutes * 60))
+    }
+
+    /// yt-dlp 에러 메시지로 원인 분류 (앞에 있는 규칙이 우선)
+    pub fn classify(message: &str) -> Self {
+        let message = message.to_lowercase();
+        let matches = |patterns: &[&str]| patterns.iter().any(|p| message.contains(p));
+
+        if matches(&[
+            "dpapi",
+            "failed to decrypt",
+            "could not copy",
+            "cookie database",
+            "could not find firefox cookies",
+            "could not find chrome cookies",
+        ]) {
+            Self::CookieExtractionFailed
+        } else if matches(&[
+            "ffmpeg is not installed",
+            "ffmpeg not found",
+            "ffprobe not found",
+        ]) {
+            Self::BinaryMissing
+        } else if matches(&[
+            "sign in to confirm your age",
+            "age-restricted",
+            "confirm your age",
+            "inappropriate for some users",
+        ]) {
+            Self::AgeRestricted
+        } else if matches(&[
+            "private video",
+            "video is private",
+            "members-only",
+            "join this channel",
+        ]) {
+            Self::Private
+        } else if matches(&[
+            "not available in your country",
+            "blocked it in your country",
+            "geo restrict",
+            "geo-restrict",
+            "not available from your location",
+        ]) {
+            Self::GeoBlocked
+        } else if matches(&[
+            "http error 429",
+            "too many requests",
+            "not a bot",
+            "rate-limit",
+            "rate limit",
+        ]) {
+            Self::RateLimited
+        } else if matches(&[
+            "video unavailable",
+            "video is unavailable",
+            "has been removed",
+            "has been terminated",
+            "does not exist",
+            "http error 404",
+            "unsupported url",
+            "is not a valid url",
+        ]) {
+            Self::Unavailable
+        } else if matches(&["requested format is not available"]) {
+            Self::FormatUnavailable
+        } else if matches(&[
+            "unable to download webpage",
+            "unable to connect",
+            "connection refused",
+            "connection reset",
+            "timed out",
+            "name or service not known",
+            "temporary failure in name resolution",
+            "getaddrinfo failed",
+            "network is unreachable",
+            "proxyerror",
+            "ssl:",
+        ]) {
+            Self::Network
+        } else {
+            Self::Unknown
+        }
+    }
+}
+
+/// 다운로드 실패 (원인 분류 + 원본 메시지)
+#[derive(Clone, Debug)]
+pub struct DownloadError {
+    pub code: DownloadErrorCode,
+    pub message: String,
+    /// 쿠키로 다시 시도한 뒤의 실패인지 (쿠키 출처가 없어 시도하지 못했으면 false)
+    pub cookies_tried: bool,
+}
+
+impl DownloadError {
+    pub fn new(code: DownloadErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            cookies_tried: false,
+        }
+    }
+
+    /// 쿠키로 다시 시도한 뒤의 실패로 표시
+    pub fn with_cookies_tried(mut self) -> Self {
+        self.cookies_tried = true;
+        self
+    }
+
+    /// 실패를 기억할 시간
+    /// (성인인증은 쿠키로 시도해 본 경우만 기억해서, 쿠키를 설정하면 바로 다시 시도되도록 함)
+    pub fn negative_cache_ttl(&self) -> Option<Duration> {
+        if self.code == DownloadErrorCode::AgeRestricted && !self.cookies_tried {
+            return None;
+        }
+        self.code.negative_cache_ttl()
+    }
+
+    /// yt-dlp stderr로 생성 (WARNING 줄에 휩쓸리지 않도록 ERROR 줄을 먼저 분류)
+    pub fn from_stderr(stderr: &str) -> Self {
+        let errors: Vec<&str> = stderr
+            .lines()
+            .filter(|line| line.contains("ERROR:"))
+            .collect();
+        let code = match DownloadErrorCode::classify(&errors.join("\n")) {
+            DownloadErrorCode::Unknown => DownloadErrorCode::classify(stderr),
+            code => code,
+        };
+        Self::new(code, format!("ERROR: {}", stderr))
+    }
+
+    /// 쿠키로 다시 시도하면 받을 수 있는 경우 (성인인증, yt-dlp가 쿠키 사용을 권하는 봇 확인 등)
+    pub fn cookies_may_help(&self) -> bool {
+        self.code == DownloadErrorCode::AgeRestricted
+            || self.message.contains("--cookies-from-browser")
+    }
+}
+
+impl fmt::Display for DownloadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for DownloadError {}
+
+impl From<String> for DownloadError {
+    fn from(message: String) -> Self {
+        Self::new(DownloadErrorCode::Unknown, message)
+    }
+}
+
+impl From<&str> for DownloadError {
+    fn from(message: &str) -> Self {
+        Self::new(DownloadErrorCode::Unknown, message)
+    }
+}
+
+impl From<std::io::Error> for DownloadError {
+    fn from(e: std::io::Error) -> Self {
+        Self::new(DownloadErrorCode::Unknown, e.to_string())
+    }
+}
@@ -1,12 +1,31 @@
 mod autostart;
+mod binaries;
+mod binary_download;
+mod checksum;
 mod config;
+mod cookies;
+mod download_error;
+mod live_stream;
 mod lyrics_server;
+mod network;
+mod playlist;
+mod thumbnails;
+mod track_mappings;
+mod video_import;
+mod video_info;
+mod video_library;
+mod video_search;
 mod video_server;
+mod video_source;
+mod video_sync;
 mod ytdlp;
 
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
+use binaries::Binaries;
+use binary_download::{BinaryDownloadState, BinaryDownloads};
+use checksum::ReleaseAsset;
 use lyrics_server::LyricsData;
 use lyrics_server::ProgressData;
 use reqwest::Client;
@@ -20,6 +39,9 @@ use tauri::{
 use tauri_plugin_updater::UpdaterExt;
 use tokio::process::Command;
 use tokio::sync::RwLock;
+use track_mappings::{TrackMapping, TrackMappingInput};
+use video_import::ImportRequest;
+use video_library::{LibraryPage, LibraryQuery, VideoEntry};
 
 const GITHUB_OWNER: &str = "ivLis-Studio";
 const GITHUB_REPO: &str = "ivLyrics-helper";
@@ -27,12 +49,15 @@ const UPDATER_USER_AGENT: &str = "ivLyrics-helper-updater";
 
 pub use config::{AppConfig, ConfigManager};
 pub use lyrics_server::LyricsServer;
+pub use track_mappings::TrackMappingStore;
+pub use video_library::VideoLibrary;
 pub use video_server::VideoServer;
-pub use ytdlp::YtDlpManager;
+pub use ytdlp::{YtDlpManager, YtDlpVersion};
 
 /// 앱 전역 상태
 pub struct AppState {
     pub ytdlp: YtDlpManager,
+    pub mappings: TrackMappingStore,
     pub config: Arc<RwLock<ConfigManager>>,
     pub lyrics: Arc<Mutex<Option<LyricsData>>>,
     pub progress: Arc<Mutex<Option<ProgressData>>>,
@@ -41,12 +66,17 @@ pub struct AppState {
 impl AppState {
     pub fn new() -> Self {
         let config_manager = ConfigManager::new();
-        let ytdlp = YtDlpManager::new(config_manager.get_video_folder());
+        let ytdlp = YtDlpManager::new(
+            config_manager.get_video_folder(),
+            config_manager.get_config(),
+        );
+        let mappings = TrackMappingStore::new(&ytdlp.data_dir());
         let lyrics = Arc::new(Mutex::new(None));
         let progress = Arc::new(Mutex::new(None));
 
         Self {
             ytdlp,
+            mappings,
             config: Arc::new(RwLock::new(config_manager)),
             lyrics,
             progress,
@@ -67,10 +97,19 @@ async fn save_config(
     state: tauri::State<'_, Arc<AppState>>,
     config: AppConfig,
 ) -> Result<(), String> {
+    network::validate(&config)?;
+    cookies::validate(&config)?;
+
     let mut config_manager = state.config.write().await;
     config_manager
         .save_config(&config)
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+    drop(config_manager);
+
+    // 실행 파일 위치/네트워크 설정이 바뀌었을 수 있으므로 다시 적용
+    state.ytdlp.apply_network_config(&config);
+    state.ytdlp.detect_binaries().await;
+    Ok(())
 }
 
 #[tauri::command]
@@ -107,6 +146,52 @@ async fn update_max_cache(
         .map_err(|e| e.to_string())
 }
 
+/// 마지막 재생 후 지정한 기간(일)이 지난 영상 자동 삭제 (0이면 사용 안 함)
+#[tauri::command]
+async fn update_max_cache_age(
+    state: tauri::State<'_, Arc<AppState>>,
+    max_cache_age_days: u32,
+) -> Result<(), String> {
+    {
+        let mut config_manager = state.config.write().await;
+        let mut config = config_manager.get_config().clone();
+        config.maxCacheAgeDays = max_cache_age_days;
+        config_manager
+            .save_config(&config)
+            .map_err(|e| e.to_string())?;
+    }
+
+    state.ytdlp.prune_cache_if_needed().await
+}
+
+/// 영상 고정/해제 (고정된 영상은 모든 모드/구간 파일이 캐시 정리에서 제외)
+///
+/// video_id는 캐시 키를 받아도 되며 id 부분(`VideoSource::key_id`)으로 저장
+#[tauri::command]
+async fn set_video_pinned(
+    state: tauri::State<'_, Arc<AppState>>,
+    video_id: String,
+    pinned: bool,
+) -> Result<(), String> {
+    let mut config_manager = state.config.write().await;
+    let mut config = config_manager.get_config().clone();
+    let key_id = ytdlp::cache_key_id(&video_id).to_string();
+    config
+        .pinnedVideos
+        .retain(|id| ytdlp::cache_key_id(id) != key_id);
+    if pinned {
+        config.pinnedVideos.push(key_id);
+    }
+    config_manager
+        .save_config(&config)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn prune_cache(state: tauri::State<'_, Arc<AppState>>) -> Result<(), String> {
+    state.ytdlp.prune_cache_if_needed().await
+}
+
 #[tauri::command]
 async fn update_start_minimized(
     state: tauri::State<'_, Arc<AppState>>,
@@ -137,7 +222,33 @@ async fn update_start_on_boot(
 
 #[tauri::command]
 async fn check_ytdlp_exists(state: tauri::State<'_, Arc<AppState>>) -> Result<bool, String> {
-    Ok(state.ytdlp.ytdlp_path().exists())
+    Ok(state
+        .ytdlp
+        .binaries()
+        .await
+        .ytdlp
+        .is_some_and(|status| status.usable_path().is_some()))
+}
+
+/// yt-dlp/ffmpeg/deno 감지 결과 (refresh면 설정에 따라 다시 감지)
+#[tauri::command]
+async fn get_binaries(
+    state: tauri::State<'_, Arc<AppState>>,
+    refresh: Option<bool>,
+) -> Result<Binaries, String> {
+    if refresh.unwrap_or(false) {
+        Ok(state.ytdlp.detect_binaries().await)
+    } else {
+        Ok(state.ytdlp.binaries().await)
+    }
+}
+
+/// 마지막으로 다운로드에 성공한 쿠키 출처
+#[tauri::command]
+async fn get_cookie_state(
+    state: tauri::State<'_, Arc<AppState>>,
+) -> Result<cookies::CookieState, String> {
+    Ok(state.ytdlp.cookie_state().get())
 }
 
 /// 쿠키 파일을 앱 데이터 폴더에 youtube_cookie.txt로 복사
@@ -219,6 +330,37 @@ async fn download_ytdlp(state: tauri::State<'_, Arc<AppState>>) -> Result<(), St
     state.ytdlp.ensure_ytdlp().await.map_err(|e| e.to_string())
 }
 
+/// 설치된 yt-dlp와 최신 릴리스 버전 (refresh면 지금 다시 확인)
+#[tauri::command]
+async fn get_ytdlp_version(
+    state: tauri::State<'_, Arc<AppState>>,
+    refresh: Option<bool>,
+) -> Result<YtDlpVersion, String> {
+    let version = state.ytdlp.ytdlp_version();
+    if refresh.unwrap_or(false) || version.checked_at.is_none() {
+        Ok(state.ytdlp.check_ytdlp_version().await)
+    } else {
+        Ok(version)
+    }
+}
+
+/// yt-dlp를 최신 릴리스로 업데이트
+///
+/// 실행 파일을 받아 교체하므로 HTTP로는 열지 않고 앱에서만 호출
+#[tauri::command]
+async fn update_ytdlp(state: tauri::State<'_, Arc<AppState>>) -> Result<YtDlpVersion, String> {
+    state.ytdlp.update_ytdlp(false).await
+}
+
+/// 오프라인 번들(zip)에서 yt-dlp/ffmpeg/deno 설치 (GitHub에 접속할 수 없는 환경용)
+#[tauri::command]
+async fn install_ytdlp_bundle(
+    state: tauri::State<'_, Arc<AppState>>,
+    path: String,
+) -> Result<Binaries, String> {
+    state.ytdlp.install_bundle(Path::new(&path)).await
+}
+
 #[tauri::command]
 async fn get_cache_usage(state: tauri::State<'_, Arc<AppState>>) -> Result<u64, String> {
     let config = state.config.read().await;
@@ -242,6 +384,71 @@ async fn get_cache_usage(state: tauri::State<'_, Arc<AppState>>) -> Result<u64,
     Ok(total_size)
 }
 
+/// 캐시 카탈로그 조회 (필터/페이징)
+#[tauri::command]
+async fn get_video_library(
+    state: tauri::State<'_, Arc<AppState>>,
+    query: Option<LibraryQuery>,
+) -> Result<LibraryPage, String> {
+    let library = state.ytdlp.library();
+    library.reconcile(&state.ytdlp.videos_dir());
+    Ok(library.list(&query.unwrap_or_default()))
+}
+
+/// 로컬 영상 파일을 캐시에 등록 (트랙 정보가 있으면 매핑도 저장)
+///
+/// 임의의 로컬 파일을 캐시로 복사해 /video/files로 읽을 수 있게 되므로
+/// HTTP로는 열지 않고 앱(파일 선택 창)에서만 호출
+#[tauri::command]
+async fn import_video(
+    state: tauri::State<'_, Arc<AppState>>,
+    request: ImportRequest,
+) -> Result<VideoEntry, String> {
+    video_import::import_video(&state.ytdlp, &state.mappings, request).await
+}
+
+#[tauri::command]
+async fn get_track_mappings(
+    state: tauri::State<'_, Arc<AppState>>,
+    query: Option<String>,
+) -> Result<Vec<TrackMapping>, String> {
+    Ok(state.mappings.list(query.as_deref()))
+}
+
+#[tauri::command]
+async fn save_track_mapping(
+    state: tauri::State<'_, Arc<AppState>>,
+    mapping: TrackMappingInput,
+) -> Result<TrackMapping, String> {
+    state.mappings.upsert(mapping)
+}
+
+#[tauri::command]
+async fn delete_track_mapping(
+    state: tauri::State<'_, Arc<AppState>>,
+    key: String,
+) -> Result<bool, String> {
+    Ok(state.mappings.remove(&key))
+}
+
+/// 매핑을 JSON 파일로 내보내기 (내보낸 개수 반환)
+#[tauri::command]
+async fn export_track_mappings(
+    state: tauri::State<'_, Arc<AppState>>,
+    path: String,
+) -> Result<usize, String> {
+    state.mappings.export(Path::new(&path))
+}
+
+/// 다른 사용자가 내보낸 매핑 가져오기 (가져온 개수 반환)
+#[tauri::command]
+async fn import_track_mappings(
+    state: tauri::State<'_, Arc<AppState>>,
+    path: String,
+) -> Result<usize, String> {
+    state.mappings.import(Path::new(&path))
+}
+
 #[tauri::command]
 async fn clear_cache(state: tauri::State<'_, Arc<AppState>>) -> Result<(), String> {
     let config = state.config.read().await;
@@ -254,12 +461,14 @@ async fn clear_cache(state: tauri::State<'_, Arc<AppState>>) -> Result<(), Strin
     if let Ok(entries) = std::fs::read_dir(&videos_dir) {
         for entry in entries.flatten() {
             if let Ok(metadata) = entry.metadata() {
-                if metadata.is_file() {
+                // 가져온 영상은 다시 받을 수 없으므로 남겨 둠
+                if metadata.is_file() && !state.ytdlp.is_imported_file(&entry.path()) {
                     let _ = std::fs::remove_file(entry.path());
                 }
             }
         }
     }
+    state.ytdlp.library().reconcile(&videos_dir);
 
     Ok(())
 }
@@ -307,26 +516,22 @@ async fn check_update_on_startup(app: AppHandle) {
 #[derive(Debug, Deserialize)]
 struct GitHubRelease {
     tag_name: String,
-    assets: Vec<GitHubAsset>,
-}
-
-#[derive(Debug, Deserialize, Clone)]
-struct GitHubAsset {
-    name: String,
-    browser_download_url: String,
+    assets: Vec<ReleaseAsset>,
 }
 
 #[derive(Debug, Clone)]
 struct ReleaseInfo {
     version: String,
-    asset: GitHubAsset,
+    asset: ReleaseAsset,
+    /// 체크섬 파일을 찾기 위한 전체 asset 목록
+    assets: Vec<ReleaseAsset>,
 }
 
 /// Tauri updater가 실패했을 때 GitHub API로 최신 버전을 확인
 async fn find_available_update(app: &AppHandle) -> Result<Option<String>, String> {
     let current_version = app.package_info().version.to_string();
 
-    if let Ok(updater) = app.updater() {
+    if let Ok(updater) = build_updater(app).await {
         match updater.check().await {
             Ok(Some(update)) => return Ok(Some(update.version)),
             Ok(None) => return Ok(None),
@@ -336,7 +541,8 @@ async fn find_available_update(app: &AppHandle) -> Result<Option<String>, String
         tracing::warn!("Tauri updater is not available");
     }
 
-    match fetch_latest_release_info().await {
+    let client = updater_client(app).await;
+    match fetch_latest_release_info(&client).await {
         Ok(Some(release)) => {
             if is_version_newer(&current_version, &release.version) {
                 Ok(Some(release.version))
@@ -349,9 +555,28 @@ async fn find_available_update(app: &AppHandle) -> Result<Option<String>, String
     }
 }
 
+/// 프록시 설정을 적용한 Tauri updater
+async fn build_updater(app: &AppHandle) -> Result<tauri_plugin_updater::Updater, String> {
+    let state = app.state::<Arc<AppState>>();
+    let proxy = network::proxy_url(state.config.read().await.get_config());
+
+    let mut builder = app.updater_builder();
+    if let Some(proxy) = proxy {
+        builder = builder.proxy(proxy);
+    }
+    builder.build().map_err(|e| e.to_string())
+}
+
+/// 네트워크 설정을 적용한 GitHub 업데이트용 HTTP 클라이언트
+async fn updater_client(app: &AppHandle) -> Client {
+    let state = app.state::<Arc<AppState>>();
+    let config = state.config.read().await;
+    network::build_client_with_user_agent(config.get_config(), UPDATER_USER_AGENT)
+}
+
 /// 내장 업데이트 설치 시도 (성공 여부 반환)
 async fn try_tauri_update(app: &AppHandle) -> Result<bool, String> {
-    let updater = app.updater().map_err(|e| e.to_string())?;
+    let updater = build_updater(app).await?;
 
     match updater.check().await {
         Ok(Some(update)) => {
@@ -369,7 +594,9 @@ async fn try_tauri_update(app: &AppHandle) -> Result<bool, String> {
 /// GitHub 릴리스에서 최신 인스톨러를 내려받아 실행
 async fn perform_github_update(app: &AppHandle) -> Result<bool, String> {
     let current_version = app.package_info().version.to_string();
-    let Some(release) = fetch_latest_release_info().await? else {
+    let state = app.state::<Arc<AppState>>();
+    let client = updater_client(app).await;
+    let Some(release) = fetch_latest_release_info(&client).await? else {
         return Ok(false);
     };
 
@@ -377,26 +604,20 @@ async fn perform_github_update(app: &AppHandle) -> Result<bool, String> {
         return Ok(false);
     }
 
-    let installer_path = download_asset(&release.asset).await?;
+    let installer_path = download_asset(&client, state.ytdlp.binary_downloads(), &release).await?;
     tracing::info!("Downloaded fallback installer to {:?}", installer_path);
     let install_result = install_downloaded(&installer_path).await;
     let _ = tokio::fs::remove_file(&installer_path).await;
     install_result.map(|_| true)
 }
 
-async fn fetch_latest_release_info() -> Result<Option<ReleaseInfo>, String> {
-    let client = Client::new();
+async fn fetch_latest_release_info(client: &Client) -> Result<Option<ReleaseInfo>, String> {
     let url = format!(
         "https://api.github.com/repos/{}/{}/releases/latest",
         GITHUB_OWNER, GITHUB_REPO
     );
 
-    let response = client
-        .get(url)
-        .header("User-Agent", UPDATER_USER_AGENT)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
+    let response = client.get(url).send().await.map_err(|e| e.to_string())?;
 
     if !response.status().is_success() {
         return Err(format!(
@@ -409,7 +630,11 @@ async fn fetch_latest_release_info() -> Result<Option<ReleaseInfo>, String> {
     let version = normalize_version(&release.tag_name);
     let asset = select_asset(&release.assets);
 
-    Ok(asset.map(|asset| ReleaseInfo { version, asset }))
+    Ok(asset.map(|asset| ReleaseInfo {
+        version,
+        asset,
+        assets: release.assets,
+    }))
 }
 
 fn normalize_version(tag: &str) -> String {
@@ -423,7 +648,7 @@ fn is_version_newer(current: &str, latest: &str) -> bool {
     }
 }
 
-fn select_asset(assets: &[GitHubAsset]) -> Option<GitHubAsset> {
+fn select_asset(assets: &[ReleaseAsset]) -> Option<ReleaseAsset> {
     #[cfg(target_os = "windows")]
     let preferred = [".exe", ".msi"];
     #[cfg(target_os = "macos")]
@@ -444,31 +669,42 @@ fn select_asset(assets: &[GitHubAsset]) -> Option<GitHubAsset> {
         .or_else(|| assets.first().cloned())
 }
 
-async fn download_asset(asset: &GitHubAsset) -> Result<PathBuf, String> {
-    let client = Client::new();
-    let response = client
-        .get(&asset.browser_download_url)
-        .header("User-Agent", UPDATER_USER_AGENT)
-        .send()
+/// 인스톨러를 받아 릴리스에 공개된 SHA-256과 비교 (맞지 않으면 실행하지 않음)
+///
+/// 임시 파일(.part)에 나눠 받고 확인이 끝난 뒤에 최종 이름으로 바꿈
+async fn download_asset(
+    client: &Client,
+    downloads: &BinaryDownloads,
+    release: &ReleaseInfo,
+) -> Result<PathBuf, String> {
+    let asset = &release.asset;
+    let path = std::env::temp_dir().join(&asset.name);
+    let part_path = std::env::temp_dir().join(format!("{}.part", asset.name));
+
+    let sha256 = downloads
+        .download_to_file(client, &asset.browser_download_url, &asset.name, &part_path)
+        .await?;
+    let installed = match checksum::verify_asset(client, &release.assets, asset, &sha256)
         .await
-        .map_err(|e| e.to_string())?;
+        .ensure_verified()
+    {
+        Ok(()) => tokio::fs::rename(&part_path, &path)
+            .await
+            .map_err(|e| e.to_string()),
+        Err(e) => Err(e),
+    };
 
-    if !response.status().is_success() {
-        return Err(format!(
-            "Failed to download installer: {}",
-            response.status()
-        ));
+    match installed {
+        Ok(()) => {
+            downloads.set_state(&asset.name, BinaryDownloadState::Completed, None);
+            Ok(path)
+        }
+        Err(e) => {
+            let _ = tokio::fs::remove_file(&part_path).await;
+            downloads.set_state(&asset.name, BinaryDownloadState::Failed, Some(e.clone()));
+            Err(e)
+        }
     }
-
-    let bytes = response.bytes().await.map_err(|e| e.to_string())?;
-    let mut path = std::env::temp_dir();
-    path.push(&asset.name);
-
-    tokio::fs::write(&path, bytes)
-        .await
-        .map_err(|e| e.to_string())?;
-
-    Ok(path)
 }
 
 async fn install_downloaded(path: &Path) -> Result<(), String> {
@@ -572,20 +808,36 @@ pub fn run() {
             get_default_video_folder,
             update_video_folder,
             update_max_cache,
+            update_max_cache_age,
+            set_video_pinned,
+            prune_cache,
             update_start_minimized,
             update_start_on_boot,
             check_ytdlp_exists,
+            get_binaries,
+            get_cookie_state,
             update_cookies_file,
             has_cookies_file,
             clear_cookies_file,
             download_ytdlp,
+            get_ytdlp_version,
+            update_ytdlp,
+            install_ytdlp_bundle,
             get_cache_usage,
+            get_video_library,
+            import_video,
+            get_track_mappings,
+            save_track_mapping,
+            delete_track_mapping,
+            export_track_mappings,
+            import_track_mappings,
             clear_cache,
             check_for_updates,
             install_update,
         ])
         .setup(move |app| {
             let app_state = app_state_for_server.clone();
+            let app_state_for_events = app_state.clone();
 
             // 트레이 아이콘 메뉴 생성
             let show_item = MenuItem::with_id(app, "show", "Show Window", true, None::<&str>)?;
@@ -642,10 +894,23 @@ pub fn run() {
                 let rt = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
                 rt.block_on(async {
                     // 비디오, 가사 API 시작 및 병합
-                    let video_router = VideoServer::new(app_state.ytdlp.clone()).get_router();
-                    let lyrics_router =
-                        LyricsServer::new(app_state.progress.clone(), app_state.lyrics.clone())
-                            .get_router();
+                    // 가사 서버의 트랙 변경 이벤트로 비디오 프리페치
+                    let (track_tx, track_rx) = tokio::sync::mpsc::unbounded_channel();
+                    let video_router = VideoServer::new(
+                        app_state.ytdlp.clone(),
+                        app_state.mappings.clone(),
+                        track_rx,
+                    )
+                    .get_router();
+                    let lyrics_router = LyricsServer::new(
+                        app_state.progress.clone(),
+                        app_state.lyrics.clone(),
+                        track_tx,
+                    )
+                    .get_router();
+
+                    // yt-dlp 새 릴리스 주기적 확인
+                    tokio::spawn(app_state.ytdlp.clone().run_update_checks());
 
                     let app = axum::Router::new()
                         .merge(video_router)
@@ -670,6 +935,21 @@ pub fn run() {
                 });
             });
 
+            // yt-dlp/Deno/인스톨러 다운로드 진행 상황을 프론트엔드에 전달
+            let mut download_rx = app_state_for_events.ytdlp.binary_downloads().subscribe();
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    match download_rx.recv().await {
+                        Ok(progress) => {
+                            let _ = app_handle.emit("download-ytdlp-progress", progress);
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                    }
+                }
+            });
+
             // 백그라운드에서 업데이트 체크
             let app_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
@@ -0,0 +1,218 @@
+use axum::{
+    body::{Body, Bytes},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use std::io::SeekFrom;
+use std::path::Path;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeekExt};
+use tokio::sync::broadcast::{self, error::TryRecvError};
+use tokio::time::{sleep, Duration, Instant};
+
+use crate::ytdlp::DownloadProgress;
+
+/// 한 번에 읽어 보내는 크기
+const CHUNK_SIZE: usize = 64 * 1024;
+/// 파일이 더 쓰이기를 기다리는 간격
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// Range 요청 위치까지 받아지기를 기다리는 최대 시간
+const RANGE_WAIT: Duration = Duration::from_secs(10);
+
+/// 확장자로 Content-Type 결정
+fn content_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("webm") => "video/webm",
+        Some("mp4") => "video/mp4",
+        Some("m4a") => "audio/mp4",
+        Some("mkv") => "video/x-matroska",
+        _ => "application/octet-stream",
+    }
+}
+
+/// 다운로드가 끝났는지 (진행 채널이 닫혔는지)
+fn is_finished(progress: &mut broadcast::Receiver<DownloadProgress>) -> bool {
+    loop {
+        match progress.try_recv() {
+            Ok(_) | Err(TryRecvError::Lagged(_)) => continue,
+            Err(TryRecvError::Empty) => return false,
+            Err(TryRecvError::Closed) => return true,
+        }
+    }
+}
+
+/// `Range` 헤더
+enum RangeHeader {
+    /// Range 요청이 아님 (bytes 이외의 단위 포함)
+    Absent,
+    /// `bytes=start-` 또는 `bytes=start-end`
+    From(u64, Option<u64>),
+    /// 끝에서부터(`bytes=-N`), 여러 구간 등 전체 길이를 모르는 동안 처리할 수 없는 요청
+    Unsupported,
+}
+
+/// `Range: bytes=start-end` 파싱 (전체 길이를 모르므로 단일 구간, 시작 위치 지정만 지원)
+fn parse_range(headers: &HeaderMap) -> RangeHeader {
+    let Some(value) = headers.get(header::RANGE) else {
+        return RangeHeader::Absent;
+    };
+    let Some(spec) = value.to_str().ok().and_then(|v| v.strip_prefix("bytes=")) else {
+        return RangeHeader::Absent;
+    };
+    let parsed = spec.split_once('-').and_then(|(start, end)| {
+        let start = start.trim().parse().ok()?;
+        let end = match end.trim() {
+            "" => None,
+            end => Some(end.parse().ok()?),
+        };
+        Some((start, end))
+    });
+    match parsed {
+        Some((start, end)) => RangeHeader::From(start, end),
+        None => RangeHeader::Unsupported,
+    }
+}
+
+/// 416 응답 (전체 길이를 모르면, 즉 아직 받는 중이면 `*`)
+fn range_not_satisfiable(complete_length: Option<u64>) -> Response {
+    let complete_length = complete_length.map_or_else(|| "*".to_string(), |len| len.to_string());
+    (
+        StatusCode::RANGE_NOT_SATISFIABLE,
+        [(
+            header::CONTENT_RANGE,
+            format!("bytes */{}", complete_length),
+        )],
+    )
+        .into_response()
+}
+
+async fn file_len(file: &tokio::fs::File) -> u64 {
+    file.metadata().await.map(|m| m.len()).unwrap_or(0)
+}
+
+/// 다운로드가 끝났으면 파일의 전체 길이
+async fn complete_length(
+    file: &tokio::fs::File,
+    progress: &mut broadcast::Receiver<DownloadProgress>,
+) -> Option<u64> {
+    if is_finished(progress) {
+        Some(file_len(file).await)
+    } else {
+        None
+    }
+}
+
+/// 아직 쓰는 중인 파일 서빙
+///
+/// - Range 요청: 지금까지 받은 부분만 206으로 응답 (전체 길이는 `*`, 416은 다 받았으면 실제 길이)
+/// - 일반 요청: 다운로드가 끝날 때까지 파일 끝을 따라가며 전송
+pub async fn serve_growing_file(
+    path: &Path,
+    headers: &HeaderMap,
+    mut progress: broadcast::Receiver<DownloadProgress>,
+) -> Response {
+    let mut file = match tokio::fs::File::open(path).await {
+        Ok(file) => file,
+        Err(e) => return (StatusCode::NOT_FOUND, e.to_string()).into_response(),
+    };
+    let content_type = content_type(path);
+
+    let (start, end) = match parse_range(headers) {
+        RangeHeader::From(start, end) => (start, end),
+        RangeHeader::Unsupported => {
+            return range_not_satisfiable(complete_length(&file, &mut progress).await)
+        }
+        RangeHeader::Absent => {
+            let body = Body::from_stream(tail_chunks(file, progress));
+            return (
+                [
+                    (header::CONTENT_TYPE, content_type),
+                    (header::ACCEPT_RANGES, "bytes"),
+                    (header::CACHE_CONTROL, "no-store"),
+                ],
+                body,
+            )
+                .into_response();
+        }
+    };
+
+    // 요청 위치까지 받아질 때까지 잠시 대기
+    let deadline = Instant::now() + RANGE_WAIT;
+    let mut available = file_len(&file).await;
+    while available <= start && !is_finished(&mut progress) && Instant::now() < deadline {
+        sleep(POLL_INTERVAL).await;
+        available = file_len(&file).await;
+    }
+    let end = end.unwrap_or(u64::MAX).min(available.saturating_sub(1));
+    if available <= start || end < start {
+        return range_not_satisfiable(complete_length(&file, &mut progress).await);
+    }
+
+    if let Err(e) = file.seek(SeekFrom::Start(start)).await {
+        return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response();
+    }
+    let length = end - start + 1;
+    let body = Body::from_stream(read_chunks(file.take(length)));
+
+    (
+        StatusCode::PARTIAL_CONTENT,
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (header::ACCEPT_RANGES, "bytes".to_string()),
+            (header::CACHE_CONTROL, "no-store".to_string()),
+            (header::CONTENT_RANGE, format!("bytes {}-{}/*", start, end)),
+            (header::CONTENT_LENGTH, length.to_string()),
+        ],
+        body,
+    )
+        .into_response()
+}
+
+/// 정해진 길이만큼 읽어 보내는 스트림
+fn read_chunks<R>(
+    reader: R,
+) -> impl futures::Stream<Item = Result<Bytes, std::io::Error>> + Send + 'static
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    futures::stream::unfold(Some(reader), |state| async move {
+        let mut reader = state?;
+        let mut buf = vec![0u8; CHUNK_SIZE];
+        match reader.read(&mut buf).await {
+            Ok(0) => None,
+            Ok(n) => {
+                buf.truncate(n);
+                Some((Ok(Bytes::from(buf)), Some(reader)))
+            }
+            Err(e) => Some((Err(e), None)),
+        }
+    })
+}
+
+/// 파일 끝에 도달하면 더 쓰일 때까지 기다리고, 다운로드가 끝나면 남은 부분까지 보내고 종료
+fn tail_chunks(
+    file: tokio::fs::File,
+    progress: broadcast::Receiver<DownloadProgress>,
+) -> impl futures::Stream<Item = Result<Bytes, std::io::Error>> + Send + 'static {
+    futures::stream::unfold(Some((file, progress)), |state| async move {
+        let (mut file, mut progress) = state?;
+        let mut buf = vec![0u8; CHUNK_SIZE];
+        let mut finished = false;
+        loop {
+            match file.read(&mut buf).await {
+                // 종료를 확인한 뒤 한 번 더 읽어도 비어 있으면 끝
+                Ok(0) if finished => return None,
+                Ok(0) => {
+                    finished = is_finished(&mut progress);
+                    if !finished {
+                        sleep(POLL_INTERVAL).await;
+                    }
+                }
+                Ok(n) => {
+                    buf.truncate(n);
+                    return Some((Ok(Bytes::from(buf)), Some((file, progress))));
+                }
+                Err(e) => return Some((Err(e), None)),
+            }
+        }
+    })
+}
@@ -1,6 +1,7 @@
 use axum::{extract::State, routing::get, routing::post, Json, Router};
 use serde::{Deserialize, Serialize};
 use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
 
 // Track info from Spotify
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -11,6 +12,8 @@ pub struct TrackInfo {
     pub album: String,
     pub album_art: Option<String>,
     pub duration: u64,
+    #[serde(default)]
+    pub uri: Option<String>, // Spotify URI (spotify:track:...)
 }
 
 // Single lyric line
@@ -56,6 +59,17 @@ pub struct NextTrackInfo {
     pub title: String,
     pub artist: String,
     pub album_art: Option<String>,
+    #[serde(default)]
+    pub uri: Option<String>,
+}
+
+// Track change notifications (used for video prefetch)
+#[derive(Debug, Clone)]
+pub enum TrackEvent {
+    // Currently playing track changed
+    Changed(TrackInfo),
+    // Upcoming track changed
+    Next(NextTrackInfo),
 }
 
 pub struct LyricsServer {
@@ -66,9 +80,10 @@ impl LyricsServer {
     pub fn new(
         progress: Arc<Mutex<Option<ProgressData>>>,
         lyrics: Arc<Mutex<Option<LyricsData>>>,
+        track_events: mpsc::UnboundedSender<TrackEvent>,
     ) -> Self {
         Self {
-            coordinator: LyricsCoordinator::new(progress, lyrics),
+            coordinator: LyricsCoordinator::new(progress, lyrics, track_events),
         }
     }
 
@@ -91,9 +106,24 @@ async fn handle_lyrics(
     Json(lyrics_data): Json<LyricsData>,
 ) -> &'static str {
     // Store in state
+    let mut track_changed = false;
     if let Ok(mut lock) = coordinator.lyrics.lock() {
+        track_changed = lock
+            .as_ref()
+            .map(|prev| {
+                (&prev.track.title, &prev.track.artist)
+                    != (&lyrics_data.track.title, &lyrics_data.track.artist)
+            })
+            .unwrap_or(true);
         *lock = Some(lyrics_data.clone());
     }
+
+    // Notify video prefetch when the track changes
+    if track_changed {
+        let _ = coordinator
+            .track_events
+            .send(TrackEvent::Changed(lyrics_data.track));
+    }
     "OK"
 }
 
@@ -102,9 +132,24 @@ async fn handle_progress(
     Json(progress_data): Json<ProgressData>,
 ) -> &'static str {
     // Store in state
+    let mut next_changed = false;
     if let Ok(mut lock) = coordinator.progress.lock() {
+        if let Some(next) = &progress_data.next_track {
+            next_changed = lock
+                .as_ref()
+                .and_then(|prev| prev.next_track.as_ref())
+                .map(|prev| (&prev.title, &prev.artist) != (&next.title, &next.artist))
+                .unwrap_or(true);
+        }
         *lock = Some(progress_data.clone());
     }
+
+    // Notify video prefetch when the upcoming track changes
+    if next_changed {
+        if let Some(next) = progress_data.next_track {
+            let _ = coordinator.track_events.send(TrackEvent::Next(next));
+        }
+    }
     "OK"
 }
 
@@ -177,14 +222,20 @@ async fn handle_get_now(
 pub struct LyricsCoordinator {
     lyrics: Arc<Mutex<Option<LyricsData>>>,
     progress: Arc<Mutex<Option<ProgressData>>>,
+    track_events: mpsc::UnboundedSender<TrackEvent>,
 }
 
 impl LyricsCoordinator {
     pub fn new(
         progress: Arc<Mutex<Option<ProgressData>>>,
         lyrics: Arc<Mutex<Option<LyricsData>>>,
+        track_events: mpsc::UnboundedSender<TrackEvent>,
     ) -> Self {
-        Self { lyrics, progress }
+        Self {
+            lyrics,
+            progress,
+            track_events,
+        }
     }
 }
 
@@ -0,0 +1,153 @@
+use reqwest::{Client, Proxy, Url};
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use crate::config::{non_empty, AppConfig};
+
+/// 사용자 지정 User-Agent가 없을 때 보내는 값 (GitHub API는 User-Agent 필수)
+const DEFAULT_USER_AGENT: &str = "ivLyrics-helper";
+
+/// 프록시로 허용하는 스킴 (yt-dlp와 reqwest가 모두 지원하는 것만)
+const PROXY_SCHEMES: [&str; 5] = ["http", "https", "socks4", "socks5", "socks5h"];
+
+/// 연결에 사용할 IP 버전
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IpFamily {
+    #[default]
+    Any,
+    Ipv4,
+    Ipv6,
+}
+
+/// 네트워크 설정 확인 (설정 저장 전에 호출)
+pub fn validate(config: &AppConfig) -> Result<(), String> {
+    if let Some(proxy) = non_empty(&config.proxy) {
+        parse_proxy(proxy)?;
+    }
+    if let Some(rate) = non_empty(&config.limitRate) {
+        if !is_valid_rate(rate) {
+            return Err(format!(
+                "Invalid rate limit {:?} (expected e.g. 500K or 4.2M)",
+                rate
+            ));
+        }
+    }
+    if let Some(address) = non_empty(&config.sourceAddress) {
+        let ip: IpAddr = address
+            .parse()
+            .map_err(|_| format!("Invalid source address {:?}", address))?;
+        let conflicts = match config.ipFamily {
+            IpFamily::Any => false,
+            IpFamily::Ipv4 => ip.is_ipv6(),
+            IpFamily::Ipv6 => ip.is_ipv4(),
+        };
+        if conflicts {
+            return Err(format!(
+                "Source address {} does not match {:?}",
+                ip, config.ipFamily
+            ));
+        }
+    }
+    if let Some(user_agent) = non_empty(&config.userAgent) {
+        if user_agent.chars().any(char::is_control) {
+            return Err("User agent must not contain control characters".to_string());
+        }
+    }
+    Ok(())
+}
+
+/// yt-dlp에 전달할 네트워크 인자 (잘못된 값은 건너뜀)
+pub fn ytdlp_args(config: &AppConfig) -> Vec<String> {
+    let mut args = Vec::new();
+
+    if let Some(proxy) = non_empty(&config.proxy).filter(|proxy| parse_proxy(proxy).is_ok()) {
+        args.push("--proxy".to_string());
+        args.push(proxy.to_string());
+    }
+    if let Some(rate) = non_empty(&config.limitRate).filter(|rate| is_valid_rate(rate)) {
+        args.push("--limit-rate".to_string());
+        args.push(rate.to_string());
+    }
+    if let Some(address) = source_address(config) {
+        args.push("--source-address".to_string());
+        args.push(address.to_string());
+    }
+    match config.ipFamily {
+        IpFamily::Any => {}
+        IpFamily::Ipv4 => args.push("--force-ipv4".to_string()),
+        IpFamily::Ipv6 => args.push("--force-ipv6".to_string()),
+    }
+    if let Some(user_agent) = non_empty(&config.userAgent) {
+        args.push("--add-headers".to_string());
+        args.push(format!("User-Agent:{}", user_agent));
+    }
+
+    args
+}
+
+/// 네트워크 설정을 적용한 HTTP 클라이언트 (썸네일, yt-dlp/Deno 설치, 앱 업데이트)
+///
+/// 대역폭 제한은 yt-dlp 다운로드에만 적용
+pub fn build_client(config: &AppConfig) -> Client {
+    build_client_with_user_agent(config, DEFAULT_USER_AGENT)
+}
+
+/// 사용자 지정 User-Agent가 없으면 `default_user_agent`를 보내는 HTTP 클라이언트
+pub fn build_client_with_user_agent(config: &AppConfig, default_user_agent: &str) -> Client {
+    let mut builder = Client::builder();
+
+    if let Some(proxy) = non_empty(&config.proxy) {
+        match parse_proxy(proxy).and_then(|url| Proxy::all(url).map_err(|e| e.to_string())) {
+            Ok(proxy) => builder = builder.proxy(proxy),
+            Err(e) => tracing::warn!("Ignoring proxy setting: {}", e),
+        }
+    }
+    // 출발 주소가 없으면 해당 IP 버전의 임의 주소에 바인딩해서 IP 버전을 강제
+    let local_address = source_address(config).or(match config.ipFamily {
+        IpFamily::Any => None,
+        IpFamily::Ipv4 => Some(IpAddr::V4(Ipv4Addr::UNSPECIFIED)),
+        IpFamily::Ipv6 => Some(IpAddr::V6(Ipv6Addr::UNSPECIFIED)),
+    });
+    if local_address.is_some() {
+        builder = builder.local_address(local_address);
+    }
+    builder = builder.user_agent(non_empty(&config.userAgent).unwrap_or(default_user_agent));
+
+    builder.build().unwrap_or_else(|e| {
+        tracing::warn!("Failed to apply network settings: {}", e);
+        Client::builder()
+            .user_agent(default_user_agent)
+            .build()
+            .unwrap_or_default()
+    })
+}
+
+/// 설정된 프록시 주소 (앱 업데이트용)
+pub fn proxy_url(config: &AppConfig) -> Option<Url> {
+    non_empty(&config.proxy).and_then(|proxy| parse_proxy(proxy).ok())
+}
+
+fn parse_proxy(proxy: &str) -> Result<Url, String> {
+    let url = Url::parse(proxy).map_err(|e| format!("Invalid proxy {:?}: {}", proxy, e))?;
+    if !PROXY_SCHEMES.contains(&url.scheme()) || url.host_str().is_none() {
+        return Err(format!(
+            "Invalid proxy {:?} (expected http, https, socks4 or socks5 URL)",
+            proxy
+        ));
+    }
+    Ok(url)
+}
+
+/// yt-dlp `--limit-rate` 형식 (`50K`, `4.2M`, 바이트/초)
+fn is_valid_rate(rate: &str) -> bool {
+    let number = rate.trim_end_matches(['K', 'M', 'G', 'k', 'm', 'g']);
+    rate.len() - number.len() <= 1
+        && number
+            .parse::<f64>()
+            .is_ok_and(|n| n.is_finite() && n > 0.0)
+}
+
+fn source_address(config: &AppConfig) -> Option<IpAddr> {
+    non_empty(&config.sourceAddress)?.parse().ok()
+}
@@ -0,0 +1,213 @@
+use serde::Serialize;
+
+use crate::video_library::now_secs;
+use crate::video_source::VideoSource;
+use crate::ytdlp::DownloadMode;
+
+/// 재생목록 일괄 다운로드 상태
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlaylistState {
+    Running,
+    Completed,
+    /// 캐시 용량이 가득 차서 남은 영상은 건너뜀
+    BudgetExceeded,
+}
+
+/// 재생목록 영상 하나의 상태
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlaylistItemStatus {
+    Pending,
+    Downloading,
+    Completed,
+    /// 이미 캐시에 있음
+    Cached,
+    Failed,
+    Skipped,
+}
+
+impl PlaylistItemStatus {
+    fn is_done(&self) -> bool {
+        !matches!(self, Self::Pending | Self::Downloading)
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct PlaylistItem {
+    pub video_id: String,
+    pub title: Option<String>,
+    /// 영상 길이 (초)
+    pub duration: Option<f64>,
+    /// 예상 파일 크기 (flat-playlist의 filesize_approx, 없으면 길이로 추정)
+    pub estimated_bytes: Option<u64>,
+    pub status: PlaylistItemStatus,
+    /// 다운로드 중인 영상의 진행률
+    pub percent: Option<f32>,
+    pub message: Option<String>,
+}
+
+/// 재생목록 전체 진행 상황 (/video/playlist 응답)
+#[derive(Clone, Debug, Serialize)]
+pub struct PlaylistProgress {
+    pub playlist_id: String,
+    pub mode: DownloadMode,
+    pub state: PlaylistState,
+    pub total: usize,
+    /// 받았거나 이미 캐시에 있던 영상 수
+    pub completed: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    /// 전체 진행률 (0~100, 다운로드 중인 영상의 진행률 포함)
+    pub percent: f64,
+    /// 다운로드 중인 비디오 id
+    pub current: Option<String>,
+    pub started_at: u64,
+    pub finished_at: Option<u64>,
+    pub items: Vec<PlaylistItem>,
+}
+
+/// 길이로 파일 크기를 추정할 때 쓰는 초당 바이트 (1080p webm/opus 기준, 넉넉하게)
+fn estimated_bytes_per_sec(mode: DownloadMode) -> f64 {
+    match mode {
+        DownloadMode::Video => 400_000.0,
+        DownloadMode::Audio => 24_000.0,
+        DownloadMode::Muxed => 424_000.0,
+    }
+}
+
+impl PlaylistProgress {
+    /// `yt-dlp --flat-playlist --dump-json` 출력으로 생성 (YouTube id가 아닌 항목은 제외)
+    pub fn new(playlist_id: &str, mode: DownloadMode, entries: &[serde_json::Value]) -> Self {
+        let items: Vec<PlaylistItem> = entries
+            .iter()
+            .filter_map(|entry| {
+                let video_id = entry.get("id")?.as_str()?;
+                VideoSource::Youtube.validate_id(video_id).ok()?;
+                let duration = entry.get("duration").and_then(|v| v.as_f64());
+                Some(PlaylistItem {
+                    video_id: video_id.to_string(),
+                    title: entry
+                        .get("title")
+                        .and_then(|v| v.as_str())
+                        .map(|v| v.to_string()),
+                    duration,
+                    estimated_bytes: entry
+                        .get("filesize_approx")
+                        .and_then(|v| v.as_f64())
+                        .or_else(|| duration.map(|d| d * estimated_bytes_per_sec(mode)))
+                        .map(|bytes| bytes as u64),
+                    status: PlaylistItemStatus::Pending,
+                    percent: None,
+                    message: None,
+                })
+            })
+            .collect();
+
+        Self {
+            playlist_id: playlist_id.to_string(),
+            mode,
+            state: PlaylistState::Running,
+            total: items.len(),
+            completed: 0,
+            failed: 0,
+            skipped: 0,
+            percent: 0.0,
+            current: None,
+            started_at: now_secs(),
+            finished_at: None,
+            items,
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.state == PlaylistState::Running
+    }
+
+    /// 영상 하나의 상태를 바꾸고 합계를 다시 계산
+    pub fn set_item(
+        &mut self,
+        index: usize,
+        status: PlaylistItemStatus,
+        percent: Option<f32>,
+        message: Option<String>,
+    ) {
+        let Some(item) = self.items.get_mut(index) else {
+            return;
+        };
+        item.status = status;
+        item.percent = percent;
+        if message.is_some() {
+            item.message = message;
+        }
+        self.current = (status == PlaylistItemStatus::Downloading).then(|| item.video_id.clone());
+        self.recount();
+    }
+
+    /// 남은 영상을 건너뛰고 종료
+    pub fn finish(&mut self, state: PlaylistState) {
+        for item in self.items.iter_mut().filter(|item| !item.status.is_done()) {
+            item.status = PlaylistItemStatus::Skipped;
+            item.percent = None;
+        }
+        self.state = state;
+        self.current = None;
+        self.finished_at = Some(now_secs());
+        self.recount();
+    }
+
+    fn recount(&mut self) {
+        let count = |wanted: &[PlaylistItemStatus]| {
+            self.items
+                .iter()
+                .filter(|item| wanted.contains(&item.status))
+                .count()
+        };
+        self.completed = count(&[PlaylistItemStatus::Completed, PlaylistItemStatus::Cached]);
+        self.failed = count(&[PlaylistItemStatus::Failed]);
+        self.skipped = count(&[PlaylistItemStatus::Skipped]);
+
+        let done = self
+            .items
+            .iter()
+            .filter(|item| item.status.is_done())
+            .count() as f64;
+        let current = self
+            .items
+            .iter()
+            .filter(|item| item.status == PlaylistItemStatus::Downloading)
+            .filter_map(|item| item.percent)
+            .map(f64::from)
+            .sum::<f64>()
+            / 100.0;
+        self.percent = if self.total == 0 {
+            100.0
+        } else {
+            ((done + current) / self.total as f64 * 100.0).min(100.0)
+        };
+    }
+}
+
+/// 재생목록 id 추출 및 확인 (재생목록 URL의 `list=`도 허용)
+pub fn parse_playlist_id(input: &str) -> Result<String, String> {
+    let input = input.trim();
+    let playlist_id = match reqwest::Url::parse(input) {
+        Ok(url) => url
+            .query_pairs()
+            .find(|(name, _)| name == "list")
+            .map(|(_, value)| value.to_string())
+            .ok_or("URL has no playlist (list=) parameter")?,
+        Err(_) => input.to_string(),
+    };
+
+    let valid = (2..=64).contains(&playlist_id.len())
+        && !playlist_id.starts_with('-')
+        && playlist_id
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_');
+    if valid {
+        Ok(playlist_id)
+    } else {
+        Err("Invalid playlist ID".to_string())
+    }
+}
@@ -0,0 +1,288 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::SystemTime;
+use tokio::process::Command;
+use tokio::sync::Mutex;
+
+use crate::video_library::VideoLibrary;
+use crate::video_source::VideoSource;
+use crate::ytdlp::YtDlpManager;
+
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+/// 스프라이트 한 칸 크기와 배치 (10x10, 160x90)
+const SPRITE_COLUMNS: u32 = 10;
+const SPRITE_ROWS: u32 = 10;
+const SPRITE_TILE_WIDTH: u32 = 160;
+const SPRITE_TILE_HEIGHT: u32 = 90;
+/// 썸네일 추출 이미지 너비
+const THUMBNAIL_WIDTH: u32 = 480;
+
+/// 미리보기 스프라이트 정보 (`{id}.sprite.json`)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SpriteInfo {
+    pub columns: u32,
+    pub rows: u32,
+    pub tile_width: u32,
+    pub tile_height: u32,
+    /// 칸 사이 간격 (초)
+    pub interval: f64,
+    /// 실제로 채워진 칸 수
+    pub count: u32,
+}
+
+/// 캐시된 영상의 썸네일/미리보기 스프라이트 관리 (data dir의 thumbs 폴더)
+#[derive(Clone)]
+pub struct ThumbnailStore {
+    thumbs_dir: PathBuf,
+    /// ffmpeg 위치는 yt-dlp와 같은 설정(ffmpegLocation)을 사용
+    ytdlp: YtDlpManager,
+    /// 생성 작업은 한 번에 하나씩 (ffmpeg 부하 및 중복 생성 방지)
+    generating: Arc<Mutex<()>>,
+}
+
+impl ThumbnailStore {
+    pub fn new(ytdlp: &YtDlpManager) -> Self {
+        Self {
+            thumbs_dir: ytdlp.data_dir().join("thumbs"),
+            ytdlp: ytdlp.clone(),
+            generating: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// 파일 이름은 캐시 키의 id 부분 (`VideoSource::key_id`)
+    fn thumbnail_path(&self, key_id: &str) -> PathBuf {
+        self.thumbs_dir.join(format!("{}.jpg", key_id))
+    }
+
+    pub fn sprite_path(&self, key_id: &str) -> PathBuf {
+        self.thumbs_dir.join(format!("{}.sprite.jpg", key_id))
+    }
+
+    fn sprite_info_path(&self, key_id: &str) -> PathBuf {
+        self.thumbs_dir.join(format!("{}.sprite.json", key_id))
+    }
+
+    /// 감지된 ffmpeg (없으면 미리보기 스프라이트 생성 불가)
+    pub async fn ffmpeg(&self) -> Option<PathBuf> {
+        self.ytdlp.ffmpeg_path().await
+    }
+
+    /// 썸네일 경로 반환 (없으면 생성)
+    ///
+    /// YouTube 영상은 썸네일을 먼저 받아보고, 실패하거나 다른 출처면 ffmpeg로 영상에서 한 프레임 추출
+    pub async fn thumbnail(
+        &self,
+        key_id: &str,
+        video_file: Option<&Path>,
+        duration: Option<f64>,
+    ) -> Result<PathBuf, String> {
+        let path = self.thumbnail_path(key_id);
+        if path.exists() {
+            return Ok(path);
+        }
+
+        let _guard = self.generating.lock().await;
+        if path.exists() {
+            return Ok(path);
+        }
+        tokio::fs::create_dir_all(&self.thumbs_dir)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let fetched = match VideoSource::from_key_id(key_id) {
+            (VideoSource::Youtube, video_id) => self.fetch_thumbnail(&video_id, &path).await,
+            (source, _) => Err(format!(
+                "No online thumbnail for {} videos",
+                source.as_str()
+            )),
+        };
+        if fetched.is_ok() {
+            return Ok(path);
+        }
+
+        match (video_file, self.ffmpeg().await) {
+            (Some(video_file), Some(ffmpeg)) => {
+                // 인트로의 검은 화면을 피하도록 영상 10% 지점에서 추출
+                let seek = duration.map(|d| d * 0.1).unwrap_or(5.0);
+                let temp_path = path.with_extension("tmp.jpg");
+                run_quiet(
+                    &ffmpeg,
+                    &[
+                        "-y",
+                        "-ss",
+                        &format!("{:.2}", seek),
+                        "-i",
+                        &video_file.to_string_lossy(),
+                        "-frames:v",
+                        "1",
+                        "-vf",
+                        &format!("scale={}:-2", THUMBNAIL_WIDTH),
+                        &temp_path.to_string_lossy(),
+                    ],
+                )
+                .await?;
+                tokio::fs::rename(&temp_path, &path)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok(path)
+            }
+            _ => fetched.map(|_| path),
+        }
+    }
+
+    async fn fetch_thumbnail(&self, video_id: &str, path: &Path) -> Result<(), String> {
+        let url = format!("https://i.ytimg.com/vi/{}/hqdefault.jpg", video_id);
+        let response = self
+            .ytdlp
+            .http_client()
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("Thumbnail request failed: {}", response.status()));
+        }
+        let bytes = response.bytes().await.map_err(|e| e.to_string())?;
+        tokio::fs::write(path, bytes)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// 미리보기 스프라이트 정보 반환 (없으면 ffmpeg로 생성)
+    ///
+    /// 영상 전체를 SPRITE_COLUMNS x SPRITE_ROWS 칸에 고르게 나눠 담음
+    pub async fn sprite(
+        &self,
+        key_id: &str,
+        video_file: &Path,
+        duration: f64,
+    ) -> Result<SpriteInfo, String> {
+        if let Some(info) = self.read_sprite_info(key_id).await {
+            return Ok(info);
+        }
+        let ffmpeg = self
+            .ffmpeg()
+            .await
+            .ok_or("ffmpeg is required for preview sprites")?;
+
+        let _guard = self.generating.lock().await;
+        if let Some(info) = self.read_sprite_info(key_id).await {
+            return Ok(info);
+        }
+        tokio::fs::create_dir_all(&self.thumbs_dir)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let tiles = SPRITE_COLUMNS * SPRITE_ROWS;
+        let interval = (duration / tiles as f64).max(1.0);
+        let count = ((duration / interval).ceil() as u32).clamp(1, tiles);
+        let filter = format!(
+            "fps=1/{interval:.3},scale={w}:{h}:force_original_aspect_ratio=decrease,\
+             pad={w}:{h}:(ow-iw)/2:(oh-ih)/2,tile={c}x{r}",
+            interval = interval,
+            w = SPRITE_TILE_WIDTH,
+            h = SPRITE_TILE_HEIGHT,
+            c = SPRITE_COLUMNS,
+            r = SPRITE_ROWS,
+        );
+
+        let sprite_path = self.sprite_path(key_id);
+        let temp_path = sprite_path.with_extension("tmp.jpg");
+        run_quiet(
+            &ffmpeg,
+            &[
+                "-y",
+                "-i",
+                &video_file.to_string_lossy(),
+                "-an",
+                "-vf",
+                &filter,
+                "-frames:v",
+                "1",
+                &temp_path.to_string_lossy(),
+            ],
+        )
+        .await?;
+        tokio::fs::rename(&temp_path, &sprite_path)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let info = SpriteInfo {
+            columns: SPRITE_COLUMNS,
+            rows: SPRITE_ROWS,
+            tile_width: SPRITE_TILE_WIDTH,
+            tile_height: SPRITE_TILE_HEIGHT,
+            interval,
+            count,
+        };
+        let content = serde_json::to_string_pretty(&info).map_err(|e| e.to_string())?;
+        tokio::fs::write(self.sprite_info_path(key_id), content)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(info)
+    }
+
+    async fn read_sprite_info(&self, key_id: &str) -> Option<SpriteInfo> {
+        if !self.sprite_path(key_id).exists() {
+            return None;
+        }
+        let content = tokio::fs::read_to_string(self.sprite_info_path(key_id))
+            .await
+            .ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// 캐시에서 사라진 영상의 썸네일/스프라이트 삭제
+    ///
+    /// `before` 이후에 만든 파일은 건너뜀 (동시에 진행 중인 다운로드가 방금 만든 썸네일 보호)
+    pub async fn remove_orphans(&self, library: &VideoLibrary, before: SystemTime) {
+        let Ok(mut entries) = tokio::fs::read_dir(&self.thumbs_dir).await else {
+            return;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let is_recent = entry
+                .metadata()
+                .await
+                .and_then(|metadata| metadata.modified())
+                .map_or(true, |modified| modified >= before);
+            if is_recent {
+                continue;
+            }
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let key_id = file_name.split('.').next().unwrap_or_default();
+            if library.entries_for_video(key_id).is_empty() {
+                let _ = tokio::fs::remove_file(entry.path()).await;
+            }
+        }
+    }
+}
+
+/// 출력 없이 실행하고 실패하면 stderr 반환
+async fn run_quiet(program: &Path, args: &[&str]) -> Result<(), String> {
+    let mut cmd = Command::new(program);
+    cmd.args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+
+    #[cfg(windows)]
+    {
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let output = cmd.output().await.map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "{:?} failed: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
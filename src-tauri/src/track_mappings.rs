@@ -0,0 +1,315 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::video_library::{now_secs, RequestedTrack};
+use crate::video_source::VideoSource;
+
+/// 매핑 출처
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MappingSource {
+    /// 비디오 요청에서 자동으로 기록
+    #[default]
+    Auto,
+    /// 사용자가 직접 지정 (자동 기록으로 덮어쓰지 않음)
+    User,
+}
+
+/// 트랙과 YouTube 영상의 연결 정보
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TrackMapping {
+    /// 매핑 키 (Spotify URI 또는 정규화된 아티스트/제목)
+    #[serde(default)]
+    pub key: String,
+    pub title: String,
+    #[serde(default)]
+    pub artist: Option<String>,
+    #[serde(default)]
+    pub album: Option<String>,
+    /// 트랙 길이 (ms)
+    #[serde(default)]
+    pub duration: Option<u64>,
+    /// Spotify URI (예: spotify:track:...)
+    #[serde(default)]
+    pub uri: Option<String>,
+    /// 영상 출처 (기본 youtube)
+    #[serde(default)]
+    pub video_source: VideoSource,
+    pub video_id: String,
+    /// 영상에서 음악이 시작되는 위치 (ms, 없으면 길이 비교로 추정, 0이면 이미 맞음)
+    #[serde(default)]
+    pub offset_ms: Option<i64>,
+    #[serde(default)]
+    pub source: MappingSource,
+    /// 마지막 갱신 시각 (unix seconds)
+    #[serde(default)]
+    pub updated_at: u64,
+}
+
+/// 사용자 매핑 생성/수정 요청
+#[derive(Clone, Debug, Deserialize)]
+pub struct TrackMappingInput {
+    pub title: String,
+    #[serde(default)]
+    pub artist: Option<String>,
+    #[serde(default)]
+    pub album: Option<String>,
+    #[serde(default)]
+    pub duration: Option<u64>,
+    #[serde(default)]
+    pub uri: Option<String>,
+    /// 영상 출처 (기본 youtube)
+    #[serde(default)]
+    pub video_source: VideoSource,
+    pub video_id: String,
+    /// 없거나 null이면 오프셋을 지정하지 않음
+    #[serde(default)]
+    pub offset_ms: Option<i64>,
+}
+
+/// 트랙 → 영상 매핑 저장소 (data dir의 track_mappings.json에 저장)
+#[derive(Clone)]
+pub struct TrackMappingStore {
+    path: PathBuf,
+    mappings: Arc<Mutex<HashMap<String, TrackMapping>>>,
+}
+
+impl TrackMappingStore {
+    pub fn new(data_dir: &Path) -> Self {
+        let path = data_dir.join("track_mappings.json");
+        let mappings = Self::load(&path).unwrap_or_default();
+
+        Self {
+            path,
+            mappings: Arc::new(Mutex::new(mappings)),
+        }
+    }
+
+    fn load(path: &Path) -> Result<HashMap<String, TrackMapping>, String> {
+        let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let mut mappings: HashMap<String, TrackMapping> =
+            serde_json::from_str(&content).map_err(|e| e.to_string())?;
+        // 직접 편집/가져온 파일의 잘못된 id는 다운로드 인자로 쓰이지 않도록 제외
+        mappings.retain(|key, mapping| {
+            let valid = mapping.video_source.validate_id(&mapping.video_id).is_ok();
+            if !valid {
+                tracing::warn!("Skipping track mapping with invalid video id: {}", key);
+            }
+            valid
+        });
+        for (key, mapping) in mappings.iter_mut() {
+            mapping.key = key.clone();
+            // 이전 형식은 자동 매핑에도 0을 저장했으므로 지정하지 않은 것으로 봄
+            if mapping.source == MappingSource::Auto && mapping.offset_ms == Some(0) {
+                mapping.offset_ms = None;
+            }
+        }
+        Ok(mappings)
+    }
+
+    /// 트랙에 연결된 영상 찾기 (URI가 있으면 URI 우선)
+    pub fn lookup(
+        &self,
+        title: &str,
+        artist: Option<&str>,
+        uri: Option<&str>,
+    ) -> Option<TrackMapping> {
+        let mappings = self.mappings.lock().ok()?;
+        uri.filter(|u| !u.is_empty())
+            .and_then(|u| mappings.get(u))
+            .or_else(|| mappings.get(&mapping_key(title, artist)))
+            .cloned()
+    }
+
+    /// 영상에 연결된 매핑 (여러 개면 사용자 매핑, 최근 수정 순으로 우선)
+    pub fn for_video(&self, source: VideoSource, video_id: &str) -> Option<TrackMapping> {
+        let mappings = self.mappings.lock().ok()?;
+        mappings
+            .values()
+            .filter(|m| m.video_source == source && m.video_id == video_id)
+            .max_by_key(|m| (m.source == MappingSource::User, m.updated_at))
+            .cloned()
+    }
+
+    /// 전체 목록 (q가 있으면 제목/아티스트/앨범/id로 필터링, 최근 수정순)
+    pub fn list(&self, q: Option<&str>) -> Vec<TrackMapping> {
+        let Ok(mappings) = self.mappings.lock() else {
+            return Vec::new();
+        };
+        let needle = q
+            .map(str::trim)
+            .filter(|q| !q.is_empty())
+            .map(|q| q.to_lowercase());
+
+        let mut items: Vec<TrackMapping> = mappings
+            .values()
+            .filter(|m| match &needle {
+                Some(needle) => [
+                    Some(m.title.as_str()),
+                    m.artist.as_deref(),
+                    m.album.as_deref(),
+                    Some(m.video_id.as_str()),
+                ]
+                .into_iter()
+                .flatten()
+                .any(|field| field.to_lowercase().contains(needle)),
+                None => true,
+            })
+            .cloned()
+            .collect();
+        items.sort_by_key(|m| std::cmp::Reverse(m.updated_at));
+        items
+    }
+
+    /// 비디오 요청에서 알게 된 연결 기록 (사용자 매핑은 덮어쓰지 않음)
+    pub fn record(&self, track: &RequestedTrack, source: VideoSource, video_id: &str) {
+        let Ok(mut mappings) = self.mappings.lock() else {
+            return;
+        };
+        let key = track
+            .uri
+            .clone()
+            .filter(|u| !u.is_empty())
+            .unwrap_or_else(|| mapping_key(&track.title, track.artist.as_deref()));
+
+        if let Some(existing) = mappings.get(&key) {
+            if existing.source == MappingSource::User
+                || (existing.video_source == source && existing.video_id == video_id)
+            {
+                return;
+            }
+        }
+
+        mappings.insert(
+            key.clone(),
+            TrackMapping {
+                key,
+                title: track.title.clone(),
+                artist: track.artist.clone(),
+                album: track.album.clone(),
+                duration: track.duration,
+                uri: track.uri.clone(),
+                video_source: source,
+                video_id: video_id.to_string(),
+                offset_ms: None,
+                source: MappingSource::Auto,
+                updated_at: now_secs(),
+            },
+        );
+        self.save(&mappings);
+    }
+
+    /// 사용자 매핑 저장 (같은 키의 기존 매핑은 교체)
+    pub fn upsert(&self, input: TrackMappingInput) -> Result<TrackMapping, String> {
+        let title = input.title.trim();
+        let video_id = input.video_id.trim();
+        if title.is_empty() {
+            return Err("Missing title".to_string());
+        }
+        input.video_source.validate_id(video_id)?;
+
+        let non_empty = |value: Option<String>| {
+            value
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        let artist = non_empty(input.artist);
+        let uri = non_empty(input.uri);
+        let key = uri
+            .clone()
+            .unwrap_or_else(|| mapping_key(title, artist.as_deref()));
+
+        let mapping = TrackMapping {
+            key: key.clone(),
+            title: title.to_string(),
+            artist,
+            album: non_empty(input.album),
+            duration: input.duration,
+            uri,
+            video_source: input.video_source,
+            video_id: video_id.to_string(),
+            offset_ms: input.offset_ms,
+            source: MappingSource::User,
+            updated_at: now_secs(),
+        };
+
+        let mut mappings = self.mappings.lock().map_err(|e| e.to_string())?;
+        mappings.insert(key, mapping.clone());
+        self.save(&mappings);
+        Ok(mapping)
+    }
+
+    /// 매핑 삭제 (삭제된 항목이 있으면 true)
+    pub fn remove(&self, key: &str) -> bool {
+        let Ok(mut mappings) = self.mappings.lock() else {
+            return false;
+        };
+        let removed = mappings.remove(key).is_some();
+        if removed {
+            self.save(&mappings);
+        }
+        removed
+    }
+
+    /// 팀원과 공유할 수 있도록 JSON 파일로 내보내기
+    pub fn export(&self, path: &Path) -> Result<usize, String> {
+        let mappings = self.mappings.lock().map_err(|e| e.to_string())?;
+        let content = serde_json::to_string_pretty(&*mappings).map_err(|e| e.to_string())?;
+        fs::write(path, content).map_err(|e| e.to_string())?;
+        Ok(mappings.len())
+    }
+
+    /// 내보낸 JSON 파일 가져오기 (같은 키는 가져온 값으로 교체)
+    ///
+    /// `record`와 마찬가지로 자동 매핑은 기존 사용자 매핑을 덮어쓰지 않음
+    /// 반환값은 실제로 추가/교체된 매핑 수
+    pub fn import(&self, path: &Path) -> Result<usize, String> {
+        let imported = Self::load(path)?;
+
+        let mut mappings = self.mappings.lock().map_err(|e| e.to_string())?;
+        let mut count = 0;
+        for (key, mapping) in imported {
+            let keeps_user = mappings.get(&key).is_some_and(|existing| {
+                existing.source == MappingSource::User && mapping.source == MappingSource::Auto
+            });
+            if !keeps_user {
+                mappings.insert(key, mapping);
+                count += 1;
+            }
+        }
+        self.save(&mappings);
+        Ok(count)
+    }
+
+    fn save(&self, mappings: &HashMap<String, TrackMapping>) {
+        if let Some(parent) = self.path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        match serde_json::to_string_pretty(mappings) {
+            Ok(content) => {
+                if let Err(e) = fs::write(&self.path, content) {
+                    tracing::warn!("Failed to save track mappings: {}", e);
+                }
+            }
+            Err(e) => tracing::warn!("Failed to serialize track mappings: {}", e),
+        }
+    }
+}
+
+/// 대소문자/공백 차이를 무시하는 매핑 키 (`artist\u{1f}title`)
+fn mapping_key(title: &str, artist: Option<&str>) -> String {
+    let normalize = |s: &str| {
+        s.split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase()
+    };
+    format!(
+        "{}\u{1f}{}",
+        artist.map(normalize).unwrap_or_default(),
+        normalize(title)
+    )
+}
@@ -0,0 +1,211 @@
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+use crate::track_mappings::{TrackMappingInput, TrackMappingStore};
+use crate::video_library::{now_secs, RequestedTrack, VideoEntry};
+use crate::video_source::{stable_hash, VideoSource};
+use crate::ytdlp::{DownloadMode, DownloadRequest, YtDlpManager};
+
+/// 가져올 수 있는 영상 확장자 (브라우저에서 바로 재생 가능한 형식)
+const VIDEO_EXTENSIONS: &[&str] = &["mp4", "m4v", "webm", "mkv", "mov"];
+/// 가져올 수 있는 오디오 확장자
+const AUDIO_EXTENSIONS: &[&str] = &["m4a", "mp3", "ogg", "opus", "flac", "wav"];
+
+/// 로컬 파일 가져오기 요청
+#[derive(Clone, Debug, Deserialize)]
+pub struct ImportRequest {
+    /// 가져올 파일의 절대 경로
+    pub path: String,
+    /// 등록할 출처 (기본 local, 실제 YouTube 영상을 대신하려면 youtube)
+    #[serde(default)]
+    pub source: VideoSource,
+    /// 등록할 비디오 id (없으면 파일 경로 해시로 local id 생성)
+    #[serde(default)]
+    pub id: Option<String>,
+    /// 없으면 확장자로 결정 (오디오 파일은 audio, 나머지는 video)
+    #[serde(default)]
+    pub mode: Option<DownloadMode>,
+    /// 함께 매핑할 트랙 정보 (선택)
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub artist: Option<String>,
+    #[serde(default)]
+    pub album: Option<String>,
+    #[serde(default)]
+    pub duration: Option<u64>,
+    #[serde(default)]
+    pub uri: Option<String>,
+    /// true면 복사 대신 하드 링크 (다른 드라이브라 실패하면 복사)
+    #[serde(default)]
+    pub hard_link: bool,
+}
+
+impl ImportRequest {
+    fn track(&self) -> Option<RequestedTrack> {
+        let non_empty = |value: &Option<String>| {
+            value
+                .as_deref()
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(|v| v.to_string())
+        };
+        Some(RequestedTrack {
+            title: non_empty(&self.title)?,
+            artist: non_empty(&self.artist),
+            album: non_empty(&self.album),
+            duration: self.duration,
+            uri: non_empty(&self.uri),
+        })
+    }
+}
+
+/// 로컬 영상/오디오 파일을 비디오 폴더에 등록하고 카탈로그에 추가
+///
+/// 트랙 정보가 있으면 사용자 매핑으로 저장해 해당 트랙 재생 시 이 파일을 사용
+pub async fn import_video(
+    ytdlp: &YtDlpManager,
+    mappings: &TrackMappingStore,
+    request: ImportRequest,
+) -> Result<VideoEntry, String> {
+    let source_file = validate_source_file(Path::new(request.path.trim()))?;
+    let extension = source_file
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .unwrap_or_default();
+    let is_audio = AUDIO_EXTENSIONS.contains(&extension.as_str());
+
+    let (source, video_id) = match request.id.as_deref().map(str::trim) {
+        Some(id) if !id.is_empty() => {
+            request.source.validate_id(id)?;
+            (request.source, id.to_string())
+        }
+        _ => (
+            VideoSource::Local,
+            stable_hash(&source_file.to_string_lossy()),
+        ),
+    };
+    let mode = request.mode.unwrap_or(if is_audio {
+        DownloadMode::Audio
+    } else {
+        DownloadMode::Video
+    });
+    if is_audio && mode != DownloadMode::Audio {
+        return Err("Audio files can only be imported in audio mode".to_string());
+    }
+
+    let track = request.track();
+    let download_request = DownloadRequest {
+        source,
+        video_id: video_id.clone(),
+        mode,
+        clip: None,
+        track: track.clone(),
+        live: false,
+    };
+    let cache_key = download_request.cache_key();
+
+    let videos_dir = ytdlp.videos_dir();
+    tokio::fs::create_dir_all(&videos_dir)
+        .await
+        .map_err(|e| e.to_string())?;
+    let file_name = format!("{}.{}", cache_key, extension);
+    let dest = videos_dir.join(&file_name);
+    if dest == source_file {
+        return Err("File is already in the video folder".to_string());
+    }
+
+    // 같은 키의 기존 파일은 교체 (확장자가 다를 수 있음)
+    if let Some(existing) = ytdlp.cached_file(&cache_key) {
+        tokio::fs::remove_file(&existing)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    let linked = request.hard_link && tokio::fs::hard_link(&source_file, &dest).await.is_ok();
+    if !linked {
+        // 복사 중인 파일이 완료 파일로 보이지 않도록 .part로 복사 후 이름 변경
+        let temp = videos_dir.join(format!("{}.part", file_name));
+        tokio::fs::copy(&source_file, &temp)
+            .await
+            .map_err(|e| format!("Failed to copy file: {}", e))?;
+        tokio::fs::rename(&temp, &dest)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    let filesize = tokio::fs::metadata(&dest)
+        .await
+        .map(|m| m.len())
+        .unwrap_or(0);
+    let entry = VideoEntry {
+        key: cache_key,
+        source,
+        video_id: video_id.clone(),
+        mode,
+        clip: None,
+        file_name,
+        title: track.as_ref().map(|t| t.title.clone()).or_else(|| {
+            source_file
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .map(|s| s.to_string())
+        }),
+        uploader: None,
+        duration: None,
+        resolution: None,
+        codec: None,
+        filesize,
+        downloaded_at: now_secs(),
+        last_accessed: None,
+        track: track.clone(),
+        imported: true,
+    };
+    ytdlp.library().upsert(entry.clone());
+
+    if let Some(track) = track {
+        mappings.upsert(TrackMappingInput {
+            title: track.title,
+            artist: track.artist,
+            album: track.album,
+            duration: track.duration,
+            uri: track.uri,
+            video_source: source,
+            video_id,
+            offset_ms: None,
+        })?;
+    }
+
+    tracing::info!(
+        "Imported {:?} as {} ({})",
+        source_file,
+        entry.key,
+        if linked { "hard link" } else { "copy" }
+    );
+    Ok(entry)
+}
+
+/// 실제 존재하는 일반 파일이고 허용된 미디어 확장자인지 확인
+fn validate_source_file(path: &Path) -> Result<PathBuf, String> {
+    if !path.is_absolute() {
+        return Err("Path must be absolute".to_string());
+    }
+    let source = std::fs::canonicalize(path).map_err(|e| format!("File not found: {}", e))?;
+    if !source.is_file() {
+        return Err("Not a file".to_string());
+    }
+
+    let extension = source
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .unwrap_or_default();
+    if !VIDEO_EXTENSIONS.contains(&extension.as_str())
+        && !AUDIO_EXTENSIONS.contains(&extension.as_str())
+    {
+        return Err(format!("Unsupported file type: .{}", extension));
+    }
+
+    Ok(source)
+}
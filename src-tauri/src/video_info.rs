@@ -0,0 +1,123 @@
+use serde::Serialize;
+
+/// 다운로드 없이 조회한 비디오 메타데이터 (yt-dlp --dump-json)
+#[derive(Clone, Debug, Serialize)]
+pub struct VideoInfo {
+    pub video_id: String,
+    pub title: Option<String>,
+    pub channel: Option<String>,
+    pub channel_id: Option<String>,
+    /// 영상 길이 (초)
+    pub duration: Option<f64>,
+    /// 성인인증 필요 여부
+    pub age_restricted: bool,
+    pub is_live: bool,
+    pub thumbnails: Vec<ThumbnailInfo>,
+    pub formats: Vec<FormatInfo>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ThumbnailInfo {
+    pub url: String,
+    pub width: Option<u64>,
+    pub height: Option<u64>,
+}
+
+/// 다운로드 가능한 포맷 한 개
+#[derive(Clone, Debug, Serialize)]
+pub struct FormatInfo {
+    pub format_id: String,
+    pub ext: Option<String>,
+    pub resolution: Option<String>,
+    pub fps: Option<f64>,
+    pub vcodec: Option<String>,
+    pub acodec: Option<String>,
+    /// 정확한 크기를 모르면 추정치
+    pub filesize: Option<u64>,
+    /// 평균 비트레이트 (kbps)
+    pub tbr: Option<f64>,
+}
+
+impl VideoInfo {
+    /// yt-dlp --dump-json 출력 파싱
+    pub fn from_json(video_id: &str, info: &serde_json::Value) -> Self {
+        let text = |value: &serde_json::Value, key: &str| {
+            value[key]
+                .as_str()
+                .filter(|s| !s.is_empty() && *s != "none")
+                .map(|s| s.to_string())
+        };
+
+        let thumbnails = info["thumbnails"]
+            .as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|thumb| {
+                        Some(ThumbnailInfo {
+                            url: thumb["url"].as_str()?.to_string(),
+                            width: thumb["width"].as_u64(),
+                            height: thumb["height"].as_u64(),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let formats = info["formats"]
+            .as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|format| {
+                        // 스토리보드 등 재생할 수 없는 포맷 제외
+                        if format["vcodec"].as_str() == Some("none")
+                            && format["acodec"].as_str() == Some("none")
+                        {
+                            return None;
+                        }
+                        Some(FormatInfo {
+                            format_id: format["format_id"].as_str()?.to_string(),
+                            ext: text(format, "ext"),
+                            resolution: text(format, "resolution"),
+                            fps: format["fps"].as_f64(),
+                            vcodec: text(format, "vcodec"),
+                            acodec: text(format, "acodec"),
+                            filesize: format["filesize"]
+                                .as_u64()
+                                .or_else(|| format["filesize_approx"].as_u64()),
+                            tbr: format["tbr"].as_f64(),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            video_id: text(info, "id").unwrap_or_else(|| video_id.to_string()),
+            title: text(info, "title"),
+            channel: text(info, "channel").or_else(|| text(info, "uploader")),
+            channel_id: text(info, "channel_id"),
+            duration: info["duration"].as_f64(),
+            age_restricted: info["age_limit"].as_u64().unwrap_or(0) >= 18,
+            is_live: info["is_live"].as_bool().unwrap_or(false),
+            thumbnails,
+            formats,
+        }
+    }
+
+    /// 메타데이터 조회 자체가 성인인증으로 막힌 경우
+    pub fn age_restricted_only(video_id: &str) -> Self {
+        Self {
+            video_id: video_id.to_string(),
+            title: None,
+            channel: None,
+            channel_id: None,
+            duration: None,
+            age_restricted: true,
+            is_live: false,
+            thumbnails: Vec::new(),
+            formats: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,513 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::video_source::VideoSource;
+use crate::ytdlp::{cache_key_id, is_incomplete_file, ClipRange, DownloadMode, DownloadRequest};
+
+/// 캐시된 비디오 한 개의 메타데이터
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VideoEntry {
+    /// 캐시 키 (확장자를 뺀 파일 이름)
+    /// 이 필드가 생기기 전 카탈로그는 항목의 맵 키(= 비디오 id, 영상 모드 캐시 키)로 채움
+    #[serde(default)]
+    pub key: String,
+    #[serde(default)]
+    pub source: VideoSource,
+    pub video_id: String,
+    #[serde(default)]
+    pub mode: DownloadMode,
+    /// 구간만 받은 클립이면 그 범위
+    #[serde(default)]
+    pub clip: Option<ClipRange>,
+    pub file_name: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub uploader: Option<String>,
+    /// 영상 길이 (초)
+    #[serde(default)]
+    pub duration: Option<f64>,
+    #[serde(default)]
+    pub resolution: Option<String>,
+    #[serde(default)]
+    pub codec: Option<String>,
+    #[serde(default)]
+    pub filesize: u64,
+    /// 다운로드 시각 (unix seconds)
+    #[serde(default)]
+    pub downloaded_at: u64,
+    /// 마지막 접근 시각 (unix seconds)
+    #[serde(default)]
+    pub last_accessed: Option<u64>,
+    /// 이 영상을 요청할 때 재생 중이던 트랙
+    #[serde(default)]
+    pub track: Option<RequestedTrack>,
+    /// 로컬 파일에서 가져온 항목 (다시 받을 수 없으므로 캐시 정리 대상에서 제외)
+    #[serde(default)]
+    pub imported: bool,
+}
+
+/// 비디오 요청 시 함께 전달된 트랙 정보
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct RequestedTrack {
+    pub title: String,
+    #[serde(default)]
+    pub artist: Option<String>,
+    #[serde(default)]
+    pub album: Option<String>,
+    /// 트랙 길이 (ms)
+    #[serde(default)]
+    pub duration: Option<u64>,
+    /// Spotify URI
+    #[serde(default)]
+    pub uri: Option<String>,
+}
+
+/// 라이브러리 조회 조건
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct LibraryQuery {
+    /// id, 제목, 업로더, 트랙 정보에서 찾을 검색어
+    #[serde(default)]
+    pub q: Option<String>,
+    #[serde(default)]
+    pub uploader: Option<String>,
+    /// size | downloaded | accessed | title | duration
+    #[serde(default)]
+    pub sort: Option<String>,
+    /// asc | desc
+    #[serde(default)]
+    pub order: Option<String>,
+    #[serde(default)]
+    pub offset: Option<usize>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+/// 라이브러리 조회 결과 (페이지 단위)
+#[derive(Clone, Debug, Serialize)]
+pub struct LibraryPage {
+    pub total: usize,
+    pub total_size: u64,
+    pub offset: usize,
+    pub limit: usize,
+    pub items: Vec<VideoEntry>,
+}
+
+const DEFAULT_PAGE_SIZE: usize = 50;
+const MAX_PAGE_SIZE: usize = 500;
+const TOUCH_INTERVAL_SECS: u64 = 60;
+
+/// 캐시 카탈로그 (data dir의 video_library.json에 저장)
+#[derive(Clone)]
+pub struct VideoLibrary {
+    path: PathBuf,
+    entries: Arc<Mutex<HashMap<String, VideoEntry>>>,
+    /// 마지막 변경 번호 (늦게 끝난 이전 저장이 새 내용을 덮어쓰지 않도록)
+    save_generation: Arc<AtomicU64>,
+    /// 파일에 기록된 변경 번호 (쓰기도 이 잠금으로 한 번에 하나씩)
+    written_generation: Arc<Mutex<u64>>,
+}
+
+impl VideoLibrary {
+    pub fn new(data_dir: &Path) -> Self {
+        let path = data_dir.join("video_library.json");
+
+        let mut entries: HashMap<String, VideoEntry> = match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
+            Err(_) => HashMap::new(),
+        };
+        for (key, entry) in entries.iter_mut() {
+            if entry.key.is_empty() {
+                entry.key = key.clone();
+            }
+        }
+
+        Self {
+            path,
+            entries: Arc::new(Mutex::new(entries)),
+            save_generation: Arc::new(AtomicU64::new(0)),
+            written_generation: Arc::new(Mutex::new(0)),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<VideoEntry> {
+        self.entries.lock().ok()?.get(key).cloned()
+    }
+
+    /// 같은 영상의 모든 캐시 항목 (모드/구간별)
+    /// key_id는 캐시 키의 id 부분 (`VideoSource::key_id`)
+    pub fn entries_for_video(&self, key_id: &str) -> Vec<VideoEntry> {
+        let Ok(entries) = self.entries.lock() else {
+            return Vec::new();
+        };
+        entries
+            .values()
+            // URL 출처는 복원된 항목의 video_id가 이미 해시라 캐시 키로 비교
+            .filter(|entry| cache_key_id(&entry.key) == key_id)
+            .cloned()
+            .collect()
+    }
+
+    /// 항목 추가 또는 교체
+    pub fn upsert(&self, entry: VideoEntry) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(entry.key.clone(), entry);
+            self.save(&entries);
+        }
+    }
+
+    pub fn remove(&self, key: &str) {
+        if let Ok(mut entries) = self.entries.lock() {
+            if entries.remove(key).is_some() {
+                self.save(&entries);
+            }
+        }
+    }
+
+    /// 마지막 접근 시각 갱신 (Range 요청이 잦으므로 1분 단위로만 저장)
+    pub fn touch(&self, key: &str) -> bool {
+        let Ok(mut entries) = self.entries.lock() else {
+            return false;
+        };
+        let Some(entry) = entries.get_mut(key) else {
+            return false;
+        };
+        let now = now_secs();
+        if entry
+            .last_accessed
+            .map(|t| now.saturating_sub(t) >= TOUCH_INTERVAL_SECS)
+            .unwrap_or(true)
+        {
+            entry.last_accessed = Some(now);
+            self.save(&entries);
+        }
+        true
+    }
+
+    /// 캐시 정리 기준 시각 (마지막 접근, 없으면 다운로드 시각)
+    pub fn last_used(&self, key: &str) -> Option<u64> {
+        let entries = self.entries.lock().ok()?;
+        let entry = entries.get(key)?;
+        Some(entry.last_accessed.unwrap_or(entry.downloaded_at))
+    }
+
+    /// 요청된 트랙 정보가 비어 있으면 채워 넣기
+    pub fn set_track_if_missing(&self, key: &str, track: &RequestedTrack) {
+        if let Ok(mut entries) = self.entries.lock() {
+            if let Some(entry) = entries.get_mut(key) {
+                if entry.track.is_none() {
+                    entry.track = Some(track.clone());
+                    self.save(&entries);
+                }
+            }
+        }
+    }
+
+    /// 디스크 상태와 카탈로그를 맞춤
+    /// 파일이 사라진 항목은 제거하고, 카탈로그에 없는 파일은 기본 정보로 추가
+    pub fn reconcile(&self, videos_dir: &Path) {
+        let mut files: HashMap<String, (String, u64, u64)> = HashMap::new();
+        if let Ok(dir) = fs::read_dir(videos_dir) {
+            for entry in dir.flatten() {
+                let Ok(metadata) = entry.metadata() else {
+                    continue;
+                };
+                if !metadata.is_file() {
+                    continue;
+                }
+                let path = entry.path();
+                let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else {
+                    continue;
+                };
+                // 다운로드 중인 임시 파일은 제외
+                if is_incomplete_file(&path) {
+                    continue;
+                }
+                let Some(key) = path.file_stem().and_then(|s| s.to_str()) else {
+                    continue;
+                };
+                let modified = metadata
+                    .modified()
+                    .ok()
+                    .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0);
+                files.insert(
+                    key.to_string(),
+                    (file_name.to_string(), metadata.len(), modified),
+                );
+            }
+        }
+
+        let Ok(mut entries) = self.entries.lock() else {
+            return;
+        };
+        let before = entries.len();
+        entries.retain(|id, _| files.contains_key(id));
+        let mut changed = entries.len() != before;
+
+        for (key, (file_name, size, modified)) in files {
+            match entries.get_mut(&key) {
+                Some(entry) => {
+                    if entry.filesize != size || entry.file_name != file_name {
+                        entry.filesize = size;
+                        entry.file_name = file_name;
+                        changed = true;
+                    }
+                }
+                None => {
+                    // 캐시 키에서 비디오 id, 모드, 구간 복원 (예: `abc123.audio`)
+                    let request = DownloadRequest::from_cache_key(&key);
+                    entries.insert(
+                        key.clone(),
+                        VideoEntry {
+                            key,
+                            source: request.source,
+                            video_id: request.video_id,
+                            mode: request.mode,
+                            clip: request.clip,
+                            file_name,
+                            title: None,
+                            uploader: None,
+                            duration: None,
+                            resolution: None,
+                            codec: None,
+                            filesize: size,
+                            downloaded_at: modified,
+                            last_accessed: None,
+                            track: None,
+                            imported: false,
+                        },
+                    );
+                    changed = true;
+                }
+            }
+        }
+
+        if changed {
+            self.save(&entries);
+        }
+    }
+
+    /// 필터링/정렬/페이징된 목록 반환
+    pub fn list(&self, query: &LibraryQuery) -> LibraryPage {
+        let mut items: Vec<VideoEntry> = match self.entries.lock() {
+            Ok(entries) => entries.values().cloned().collect(),
+            Err(_) => Vec::new(),
+        };
+
+        if let Some(q) = query.q.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+            let needle = q.to_lowercase();
+            items.retain(|entry| entry_matches(entry, &needle));
+        }
+
+        if let Some(uploader) = query
+            .uploader
+            .as_deref()
+            .map(str::trim)
+            .filter(|u| !u.is_empty())
+        {
+            let uploader = uploader.to_lowercase();
+            items.retain(|entry| {
+                entry
+                    .uploader
+                    .as_deref()
+                    .map(|u| u.to_lowercase() == uploader)
+                    .unwrap_or(false)
+            });
+        }
+
+        let sort = query.sort.as_deref().unwrap_or("downloaded");
+        match sort {
+            "size" => items.sort_by_key(|e| e.filesize),
+            "accessed" => items.sort_by_key(|e| e.last_accessed.unwrap_or(0)),
+            "title" => {
+                items.sort_by_key(|e| e.title.as_deref().unwrap_or(&e.video_id).to_lowercase())
+            }
+            "duration" => items.sort_by(|a, b| {
+                a.duration
+                    .unwrap_or(0.0)
+                    .total_cmp(&b.duration.unwrap_or(0.0))
+            }),
+            _ => items.sort_by_key(|e| e.downloaded_at),
+        }
+
+        // 제목은 오름차순, 나머지는 내림차순이 기본
+        let default_desc = sort != "title";
+        let desc = match query.order.as_deref() {
+            Some("asc") => false,
+            Some("desc") => true,
+            _ => default_desc,
+        };
+        if desc {
+            items.reverse();
+        }
+
+        let total = items.len();
+        let total_size = items.iter().map(|e| e.filesize).sum();
+        let offset = query.offset.unwrap_or(0).min(total);
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, MAX_PAGE_SIZE);
+        let items = items.into_iter().skip(offset).take(limit).collect();
+
+        LibraryPage {
+            total,
+            total_size,
+            offset,
+            limit,
+            items,
+        }
+    }
+
+    /// 변경된 카탈로그 저장
+    ///
+    /// 잠금 안에서는 복사만 하고, 직렬화와 파일 쓰기는 blocking 스레드에서 처리
+    /// (/video/files 요청마다 async 핸들러가 파일 쓰기를 기다리지 않도록)
+    fn save(&self, entries: &HashMap<String, VideoEntry>) {
+        let generation = self.save_generation.fetch_add(1, Ordering::SeqCst) + 1;
+        let snapshot = entries.clone();
+        let path = self.path.clone();
+        let written_generation = self.written_generation.clone();
+        let write = move || {
+            let Ok(mut written) = written_generation.lock() else {
+                return;
+            };
+            if *written < generation {
+                write_library(&path, &snapshot);
+                *written = generation;
+            }
+        };
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn_blocking(write);
+            }
+            Err(_) => write(),
+        }
+    }
+}
+
+fn write_library(path: &Path, entries: &HashMap<String, VideoEntry>) {
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    match serde_json::to_string_pretty(entries) {
+        Ok(content) => {
+            if let Err(e) = fs::write(path, content) {
+                tracing::warn!("Failed to save video library: {}", e);
+            }
+        }
+        Err(e) => tracing::warn!("Failed to serialize video library: {}", e),
+    }
+}
+
+fn entry_matches(entry: &VideoEntry, needle: &str) -> bool {
+    let track = entry.track.as_ref();
+    [
+        Some(entry.video_id.as_str()),
+        entry.title.as_deref(),
+        entry.uploader.as_deref(),
+        track.map(|t| t.title.as_str()),
+        track.and_then(|t| t.artist.as_deref()),
+    ]
+    .into_iter()
+    .flatten()
+    .any(|field| field.to_lowercase().contains(needle))
+}
+
+/// 현재 시각 (unix seconds)
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// yt-dlp info.json에서 카탈로그 항목 생성
+pub fn entry_from_info_json(
+    key: &str,
+    request: &DownloadRequest,
+    file_name: &str,
+    filesize: u64,
+    info: &serde_json::Value,
+) -> VideoEntry {
+    let text = |key: &str| info[key].as_str().map(|s| s.to_string());
+
+    let resolution =
+        text("resolution").or_else(|| match (info["width"].as_u64(), info["height"].as_u64()) {
+            (Some(w), Some(h)) => Some(format!("{}x{}", w, h)),
+            _ => None,
+        });
+    // 영상 전용 스트림이면 vcodec, 오디오 전용이면 acodec
+    let codec = text("vcodec")
+        .filter(|c| c != "none")
+        .or_else(|| text("acodec").filter(|c| c != "none"));
+
+    VideoEntry {
+        key: key.to_string(),
+        source: request.source,
+        video_id: request.video_id.clone(),
+        mode: request.mode,
+        clip: request.clip,
+        file_name: file_name.to_string(),
+        title: text("title"),
+        uploader: text("uploader").or_else(|| text("channel")),
+        duration: info["duration"].as_f64(),
+        resolution,
+        codec,
+        filesize,
+        downloaded_at: now_secs(),
+        last_accessed: None,
+        track: request.track.clone(),
+        imported: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reconciled_url_entries_match_their_key_id() {
+        let videos_dir = tempfile::tempdir().unwrap();
+        let data_dir = tempfile::tempdir().unwrap();
+        let url = "https://example.com/watch?v=1";
+        let key_id = VideoSource::Url.key_id(url);
+        fs::write(videos_dir.path().join(format!("{}.mp4", key_id)), b"video").unwrap();
+        fs::write(
+            videos_dir.path().join(format!("{}.audio.m4a", key_id)),
+            b"audio",
+        )
+        .unwrap();
+
+        let library = VideoLibrary::new(data_dir.path());
+        library.reconcile(videos_dir.path());
+
+        let entries = library.entries_for_video(&key_id);
+        assert_eq!(entries.len(), 2);
+        assert!(entries.iter().all(|entry| entry.source == VideoSource::Url));
+        assert!(library.entries_for_video("dQw4w9WgXcQ").is_empty());
+    }
+
+    #[test]
+    fn loads_catalogue_without_keys() {
+        let data_dir = tempfile::tempdir().unwrap();
+        fs::write(
+            data_dir.path().join("video_library.json"),
+            r#"{"dQw4w9WgXcQ": {"video_id": "dQw4w9WgXcQ", "file_name": "dQw4w9WgXcQ.mp4", "filesize": 5}}"#,
+        )
+        .unwrap();
+
+        let library = VideoLibrary::new(data_dir.path());
+        let entry = library.get("dQw4w9WgXcQ").unwrap();
+        assert_eq!(entry.key, "dQw4w9WgXcQ");
+        assert_eq!(entry.mode, DownloadMode::Video);
+        assert_eq!(library.entries_for_video("dQw4w9WgXcQ").len(), 1);
+    }
+}
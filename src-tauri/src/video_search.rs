@@ -0,0 +1,180 @@
+use serde::Serialize;
+
+/// 트랙 검색 결과 후보 한 개
+#[derive(Clone, Debug, Serialize)]
+pub struct SearchCandidate {
+    pub video_id: String,
+    pub title: String,
+    pub channel: Option<String>,
+    /// 영상 길이 (초)
+    pub duration: Option<f64>,
+    pub view_count: Option<u64>,
+    /// 최종 점수 (0.0 ~ 1.0)
+    pub score: f64,
+    pub title_score: f64,
+    pub channel_score: f64,
+    /// 트랙 길이를 모르면 None
+    pub duration_score: Option<f64>,
+}
+
+/// 검색 대상 트랙
+pub struct SearchTrack<'a> {
+    pub title: &'a str,
+    pub artist: Option<&'a str>,
+    /// 트랙 길이 (ms)
+    pub duration_ms: Option<u64>,
+}
+
+/// 커버/라이브 등 원곡이 아닐 가능성이 높은 키워드
+const PENALTY_WORDS: &[&str] = &[
+    "cover",
+    "live",
+    "remix",
+    "karaoke",
+    "reaction",
+    "lyrics",
+    "instrumental",
+    "tutorial",
+    "piano",
+    "8d",
+    "nightcore",
+    "slowed",
+    "sped",
+];
+
+impl SearchTrack<'_> {
+    /// ytsearch에 넘길 검색어
+    pub fn query(&self) -> String {
+        match self.artist {
+            Some(artist) => format!("{} {}", artist, self.title),
+            None => self.title.to_string(),
+        }
+    }
+
+    /// yt-dlp --flat-playlist 결과 한 줄을 점수화된 후보로 변환
+    pub fn score(&self, entry: &serde_json::Value) -> Option<SearchCandidate> {
+        let video_id = entry["id"].as_str()?.to_string();
+        let title = entry["title"].as_str()?.to_string();
+        let channel = entry["channel"]
+            .as_str()
+            .or_else(|| entry["uploader"].as_str())
+            .map(|s| s.to_string());
+        let duration = entry["duration"].as_f64();
+
+        let title_score = self.title_score(&title);
+        let channel_score = self.channel_score(&title, channel.as_deref());
+        let duration_score = self
+            .duration_ms
+            .zip(duration)
+            .map(|(track_ms, video_secs)| duration_score(track_ms as f64 / 1000.0, video_secs));
+
+        let mut score = match duration_score {
+            Some(d) => title_score * 0.5 + channel_score * 0.2 + d * 0.3,
+            None => title_score * 0.7 + channel_score * 0.3,
+        };
+        score -= self.penalty(&title);
+
+        Some(SearchCandidate {
+            video_id,
+            title,
+            channel,
+            duration,
+            view_count: entry["view_count"].as_u64(),
+            score: score.clamp(0.0, 1.0),
+            title_score,
+            channel_score,
+            duration_score,
+        })
+    }
+
+    /// 트랙 제목/아티스트 단어가 영상 제목에 얼마나 포함되는지
+    fn title_score(&self, video_title: &str) -> f64 {
+        let video_tokens = tokens(video_title);
+        let title_tokens = tokens(self.title);
+        if title_tokens.is_empty() {
+            return 0.0;
+        }
+
+        let matched = title_tokens
+            .iter()
+            .filter(|t| video_tokens.contains(t))
+            .count();
+        let mut score = matched as f64 / title_tokens.len() as f64;
+
+        if let Some(artist) = self.artist {
+            let artist_tokens = tokens(artist);
+            if !artist_tokens.is_empty() && artist_tokens.iter().all(|t| video_tokens.contains(t)) {
+                score = (score + 0.2).min(1.0);
+            }
+        }
+
+        score
+    }
+
+    /// 공식 채널일 가능성 (아티스트 채널, VEVO, 자동 생성 Topic 채널, "official" 표기)
+    fn channel_score(&self, video_title: &str, channel: Option<&str>) -> f64 {
+        let channel = channel.unwrap_or_default();
+        let channel_norm = normalize(channel);
+        let title_norm = normalize(video_title);
+        let artist_norm = self.artist.map(normalize).unwrap_or_default();
+
+        let mut score: f64 = 0.0;
+        if !artist_norm.is_empty() && channel_norm.contains(&artist_norm) {
+            score = 0.8;
+        }
+        if channel.ends_with(" - Topic") || channel_norm.ends_with("vevo") {
+            score = score.max(0.7);
+        }
+        if title_norm.contains("official") {
+            score += 0.2;
+        }
+        score.min(1.0)
+    }
+
+    /// 검색어에 없던 커버/라이브 등의 키워드가 있으면 감점
+    fn penalty(&self, video_title: &str) -> f64 {
+        let video_tokens = tokens(video_title);
+        let query_tokens = tokens(&self.query());
+        let hits = PENALTY_WORDS
+            .iter()
+            .filter(|word| {
+                video_tokens.iter().any(|t| t == *word) && !query_tokens.iter().any(|t| t == *word)
+            })
+            .count();
+        (hits as f64 * 0.15).min(0.45)
+    }
+}
+
+/// 길이 차이가 3초 이내면 만점, 60초 이상이면 0점
+fn duration_score(track_secs: f64, video_secs: f64) -> f64 {
+    let diff = (track_secs - video_secs).abs();
+    if diff <= 3.0 {
+        1.0
+    } else {
+        (1.0 - (diff - 3.0) / 57.0).max(0.0)
+    }
+}
+
+/// 소문자로 바꾸고 문자/숫자 외에는 공백으로 치환
+fn normalize(text: &str) -> String {
+    text.chars()
+        .map(|c| {
+            if c.is_alphanumeric() {
+                c.to_lowercase().next().unwrap_or(c)
+            } else {
+                ' '
+            }
+        })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn tokens(text: &str) -> Vec<String> {
+    normalize(text)
+        .split(' ')
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_string())
+        .collect()
+}
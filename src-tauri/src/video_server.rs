@@ -1,54 +1,216 @@
 use axum::{
-    extract::{Query, State},
-    http::StatusCode,
+    extract::{Path, Query, Request, State},
+    http::{HeaderMap, StatusCode},
+    middleware::{self, Next},
     response::{
         sse::{Event, Sse},
-        IntoResponse, Response,
+        IntoResponse, Redirect, Response,
     },
     routing::get,
-    Router,
+    Json, Router,
 };
 use futures::stream::Stream;
-use std::{collections::HashMap, convert::Infallible, sync::Arc};
-use tokio::sync::{broadcast, Mutex};
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant, SystemTime},
+};
+use tokio::sync::{broadcast, mpsc, Mutex, Semaphore};
 use tokio_stream::wrappers::BroadcastStream;
 use tokio_stream::StreamExt;
 use tower_http::services::ServeDir;
 
-use crate::ytdlp::{DownloadProgress, DownloadStatus, YtDlpManager};
+use crate::binary_download::BinaryDownloadProgress;
+use crate::download_error::DownloadErrorCode;
+use crate::live_stream;
+use crate::lyrics_server::TrackEvent;
+use crate::playlist::{self, PlaylistItemStatus, PlaylistProgress, PlaylistState};
+use crate::thumbnails::{SpriteInfo, ThumbnailStore};
+use crate::track_mappings::{MappingSource, TrackMapping, TrackMappingInput, TrackMappingStore};
+use crate::video_info::VideoInfo;
+use crate::video_library::{LibraryPage, LibraryQuery, RequestedTrack};
+use crate::video_search::{SearchCandidate, SearchTrack};
+use crate::video_source::VideoSource;
+use crate::video_sync::SyncHint;
+use crate::ytdlp::{
+    is_safe_cache_key, ClipRange, DownloadMode, DownloadProgress, DownloadRequest, DownloadStatus,
+    YtDlpManager, YtDlpVersion,
+};
 
 /// 비디오 API 서버
 pub struct VideoServer {
     coordinator: DownloadCoordinator,
+    track_events: mpsc::UnboundedReceiver<TrackEvent>,
 }
 
 impl VideoServer {
-    pub fn new(ytdlp: YtDlpManager) -> Self {
+    pub fn new(
+        ytdlp: YtDlpManager,
+        mappings: TrackMappingStore,
+        track_events: mpsc::UnboundedReceiver<TrackEvent>,
+    ) -> Self {
         Self {
-            coordinator: DownloadCoordinator::new(ytdlp),
+            coordinator: DownloadCoordinator::new(ytdlp, mappings),
+            track_events,
         }
     }
 
-    /// Router 반환
+    /// Router 반환 (트랙 변경에 따른 프리페치 작업도 함께 시작)
     pub fn get_router(self) -> Router {
         let videos_dir = self.coordinator.ytdlp.videos_dir();
 
         let coordinator = Arc::new(self.coordinator);
+        tokio::spawn(run_prefetch(coordinator.clone(), self.track_events));
+
+        let files_router = files_router(coordinator.clone(), videos_dir);
 
         Router::new()
             .route("/video/request", get(handle_video_request))
             .route("/video/status", get(handle_video_status))
+            .route("/video/live/{key}", get(handle_video_live))
+            .route("/video/thumbs/{id}", get(handle_video_thumbnail))
+            .route("/video/thumbs/{id}/sprite", get(handle_video_sprite))
+            .route(
+                "/video/thumbs/{id}/sprite.json",
+                get(handle_video_sprite_info),
+            )
+            .route("/video/library", get(handle_video_library))
+            .route("/video/info", get(handle_video_info))
+            .route("/video/search", get(handle_video_search))
+            .route("/video/playlist", get(handle_video_playlist))
+            .route("/video/playlist/status", get(handle_playlist_status))
+            .route(
+                "/video/mappings",
+                get(handle_list_mappings)
+                    .post(handle_save_mapping)
+                    .delete(handle_delete_mapping),
+            )
+            .route("/video/mappings/lookup", get(handle_lookup_mapping))
+            .route("/video/ytdlp", get(handle_ytdlp_version))
+            .route("/video/ytdlp/progress", get(handle_ytdlp_download_progress))
             .route("/health", get(health_check))
-            // 정적 파일 서빙 (다운로드된 비디오)
-            .nest_service("/video/files", ServeDir::new(videos_dir))
             .with_state(coordinator)
+            .merge(files_router)
+    }
+}
+
+/// 정적 파일 서빙 (다운로드된 비디오) - 서빙할 때마다 마지막 재생 시각 기록
+fn files_router(coordinator: Arc<DownloadCoordinator>, videos_dir: std::path::PathBuf) -> Router {
+    Router::new()
+        .nest_service("/video/files", ServeDir::new(videos_dir))
+        .layer(middleware::from_fn_with_state(
+            coordinator,
+            record_file_access,
+        ))
+}
+
+/// 재생목록 영상 다운로드 요청 (전체 구간, 트랙 정보 없음)
+fn playlist_request(video_id: &str, mode: DownloadMode) -> DownloadRequest {
+    DownloadRequest {
+        source: VideoSource::Youtube,
+        video_id: video_id.to_string(),
+        mode,
+        clip: None,
+        track: None,
+        live: false,
     }
 }
 
 /// 쿼리 파라미터
 #[derive(serde::Deserialize)]
 struct VideoQuery {
+    /// youtube (기본) | bilibili | niconico | vimeo | url | local
+    #[serde(default)]
+    source: VideoSource,
+    /// 출처별 id (source=url이면 영상 URL)
     id: String,
+    /// video (기본, 무음 영상) | audio | muxed
+    #[serde(default)]
+    mode: DownloadMode,
+    /// 구간 다운로드 시작/끝 (`90`, `1:30`, `1:02:03.5` 형식)
+    #[serde(default)]
+    start: Option<String>,
+    #[serde(default)]
+    end: Option<String>,
+    /// 요청 시점에 재생 중인 트랙 정보 (선택)
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    artist: Option<String>,
+    #[serde(default)]
+    album: Option<String>,
+    #[serde(default)]
+    duration: Option<u64>,
+    /// Spotify URI
+    #[serde(default)]
+    uri: Option<String>,
+    /// true면 다운로드 완료를 기다리지 않고 /video/live URL을 바로 반환
+    #[serde(default)]
+    live: bool,
+    /// true면 최근 실패 기록(비공개, 삭제 등)을 무시하고 다시 시도
+    #[serde(default)]
+    retry: bool,
+}
+
+impl VideoQuery {
+    /// 트랙 제목이 있으면 카탈로그에 기록할 트랙 정보 생성
+    fn track(&self) -> Option<RequestedTrack> {
+        let title = self.title.as_deref()?.trim();
+        if title.is_empty() {
+            return None;
+        }
+        let non_empty = |value: &Option<String>| {
+            value
+                .as_deref()
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(|v| v.to_string())
+        };
+        Some(RequestedTrack {
+            title: title.to_string(),
+            artist: non_empty(&self.artist),
+            album: non_empty(&self.album),
+            duration: self.duration,
+            uri: non_empty(&self.uri),
+        })
+    }
+
+    fn download_request(&self) -> Result<DownloadRequest, String> {
+        let clip = ClipRange::parse(self.start.as_deref(), self.end.as_deref())?;
+        // 병합/구간 추출은 ffmpeg 후처리가 끝나야 파일이 완성되므로 스트리밍 불가
+        if self.live && (self.mode == DownloadMode::Muxed || clip.is_some()) {
+            return Err(
+                "Live streaming supports only video/audio mode without start/end".to_string(),
+            );
+        }
+
+        let video_id = self.id.trim();
+        self.source.validate_id(video_id)?;
+
+        Ok(DownloadRequest {
+            source: self.source,
+            video_id: video_id.to_string(),
+            mode: self.mode,
+            clip,
+            track: self.track(),
+            live: self.live,
+        })
+    }
+}
+
+/// 캐시 파일의 서빙 URL
+fn file_url(path: &std::path::Path) -> String {
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    format!("http://localhost:15123/video/files/{}", file_name)
+}
+
+/// 다운로드 중 스트리밍 URL
+fn live_url(cache_key: &str) -> String {
+    format!("http://localhost:15123/video/live/{}", cache_key)
 }
 
 /// 비디오 응답
@@ -58,6 +220,43 @@ struct VideoResponse {
     video_id: String,
     url: Option<String>,
     message: Option<String>,
+    /// 영상이 있을 때 offset_ms, playback_rate, sync_source 포함
+    #[serde(flatten)]
+    sync: Option<SyncHint>,
+    /// 다운로드 실패 원인 (age_restricted, private, geo_blocked 등)
+    error_code: Option<DownloadErrorCode>,
+    /// 최근 실패 기록이 만료되기까지 남은 시간 (초, retry=true면 바로 다시 시도)
+    retry_after: Option<u64>,
+}
+
+/// 잘못된 요청 응답
+fn bad_request(video_id: &str, message: String) -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        axum::Json(VideoResponse {
+            success: false,
+            video_id: video_id.to_string(),
+            url: None,
+            message: Some(message),
+            sync: None,
+            error_code: None,
+            retry_after: None,
+        }),
+    )
+        .into_response()
+}
+
+/// 최근에 실패한 영상 응답 본문
+fn failure_response(video_id: &str, failure: &FailedDownload) -> VideoResponse {
+    VideoResponse {
+        success: false,
+        video_id: video_id.to_string(),
+        url: None,
+        message: Some(failure.message.clone()),
+        sync: None,
+        error_code: Some(failure.code),
+        retry_after: Some(failure.retry_after().as_secs()),
+    }
 }
 
 /// 헬스 체크
@@ -66,10 +265,12 @@ async fn health_check() -> &'static str {
 }
 
 /// 비디오 다운로드 및 URL 반환 엔드포인트
-/// GET /video/request?id=<youtube_id>
+/// GET /video/request?id=<youtube_id>&mode=<video|audio|muxed>&start=<time>&end=<time>&live=<bool>&retry=<bool>
 ///
 /// 이미 존재하면 즉시 URL 반환
+/// 최근에 비공개/삭제 등으로 실패한 영상은 다시 받지 않고 error_code와 함께 바로 실패 응답
 /// 없으면 다운로드 시작하고 SSE로 진행상황 스트리밍
+/// live=true면 다운로드를 시작하고 받는 중에도 재생할 수 있는 /video/live URL을 바로 반환
 async fn handle_video_request(
     State(coordinator): State<Arc<DownloadCoordinator>>,
     Query(query): Query<VideoQuery>,
@@ -77,44 +278,73 @@ async fn handle_video_request(
     let video_id = query.id.trim();
     let ytdlp = &coordinator.ytdlp;
 
-    // 유효성 검사
-    if video_id.is_empty() || video_id.len() > 20 {
-        return (
-            StatusCode::BAD_REQUEST,
-            axum::Json(VideoResponse {
-                success: false,
-                video_id: video_id.to_string(),
-                url: None,
-                message: Some("Invalid video ID".to_string()),
-            }),
-        )
-            .into_response();
-    }
+    // 유효성 검사 (출처별 id 형식, 구간, 모드 조합)
+    let request = match query.download_request() {
+        Ok(request) => request,
+        Err(e) => return bad_request(video_id, e),
+    };
+    let cache_key = request.cache_key();
 
     // 이미 존재하는 경우 바로 응답
-    if ytdlp.video_exists(video_id) {
-        let video_path = ytdlp.video_path(video_id);
-        let default_name = format!("{}.webm", video_id);
-        let file_name = video_path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .map(|s| s.to_string())
-            .unwrap_or(default_name);
+    if let Some(video_path) = ytdlp.cached_file(&cache_key) {
+        if let Some(track) = &request.track {
+            ytdlp.library().set_track_if_missing(&cache_key, track);
+        }
 
         return axum::Json(VideoResponse {
             success: true,
             video_id: video_id.to_string(),
-            url: Some(format!("http://localhost:15123/video/files/{}", file_name)),
+            url: Some(file_url(&video_path)),
             message: Some("Video already available".to_string()),
+            sync: Some(coordinator.sync_hint(&request)),
+            error_code: None,
+            retry_after: None,
+        })
+        .into_response();
+    }
+
+    // 최근 실패 기록이 있으면 yt-dlp를 다시 실행하지 않음 (retry=true면 기록 삭제 후 시도)
+    if query.retry {
+        coordinator.forget_failure(&request);
+    } else if let Some(failure) = coordinator.known_failure(&request) {
+        let status = match failure.code {
+            DownloadErrorCode::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+            DownloadErrorCode::GeoBlocked => StatusCode::UNAVAILABLE_FOR_LEGAL_REASONS,
+            DownloadErrorCode::Private | DownloadErrorCode::AgeRestricted => StatusCode::FORBIDDEN,
+            _ => StatusCode::NOT_FOUND,
+        };
+        return (status, Json(failure_response(video_id, &failure))).into_response();
+    }
+
+    // 트랙 정보가 있으면 다음 재생 때 프리페치할 수 있도록 매핑 기록
+    if let Some(track) = &request.track {
+        coordinator
+            .mappings
+            .record(track, request.source, &request.video_id);
+    }
+
+    if request.live {
+        let sync = coordinator.sync_hint(&request);
+        let url = live_url(&cache_key);
+        let _ = coordinator.start_or_subscribe(request).await;
+
+        return axum::Json(VideoResponse {
+            success: true,
+            video_id: video_id.to_string(),
+            url: Some(url),
+            message: Some("Streaming while downloading".to_string()),
+            sync: Some(sync),
+            error_code: None,
+            retry_after: None,
         })
         .into_response();
     }
 
     // 진행 중 다운로드가 있으면 합류, 없으면 새 다운로드 시작
-    let progress_rx = coordinator.start_or_subscribe(video_id).await;
+    let progress_rx = coordinator.start_or_subscribe(request.clone()).await;
 
-    // SSE 스트림 생성
-    let stream = create_progress_stream(progress_rx);
+    // SSE 스트림 생성 (완료 이벤트에 싱크 힌트 포함)
+    let stream = create_progress_stream(progress_rx, coordinator.clone(), request);
 
     Sse::new(stream)
         .keep_alive(axum::response::sse::KeepAlive::default())
@@ -122,52 +352,739 @@ async fn handle_video_request(
 }
 
 /// 비디오 상태 확인 엔드포인트 (SSE 없이 단순 조회)
-/// GET /video/status?id=<youtube_id>
+/// GET /video/status?id=<youtube_id>&mode=<video|audio|muxed>&start=<time>&end=<time>
 async fn handle_video_status(
     State(coordinator): State<Arc<DownloadCoordinator>>,
     Query(query): Query<VideoQuery>,
-) -> axum::Json<VideoResponse> {
+) -> Response {
     let video_id = query.id.trim();
     let ytdlp = &coordinator.ytdlp;
+    let request = match query.download_request() {
+        Ok(request) => request,
+        Err(e) => return bad_request(video_id, e),
+    };
 
-    if ytdlp.video_exists(video_id) {
-        let video_path = ytdlp.video_path(video_id);
-        let default_name = format!("{}.webm", video_id);
-        let file_name = video_path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .map(|s| s.to_string())
-            .unwrap_or(default_name);
-
+    if let Some(video_path) = ytdlp.cached_file(&request.cache_key()) {
         axum::Json(VideoResponse {
             success: true,
             video_id: video_id.to_string(),
-            url: Some(format!("http://localhost:15123/video/files/{}", file_name)),
+            url: Some(file_url(&video_path)),
             message: Some("Video available".to_string()),
+            sync: Some(coordinator.sync_hint(&request)),
+            error_code: None,
+            retry_after: None,
         })
+        .into_response()
+    } else if let Some(failure) = coordinator.known_failure(&request) {
+        axum::Json(failure_response(video_id, &failure)).into_response()
     } else {
         axum::Json(VideoResponse {
             success: false,
             video_id: video_id.to_string(),
             url: None,
             message: Some("Video not downloaded".to_string()),
+            sync: None,
+            error_code: None,
+            retry_after: None,
         })
+        .into_response()
+    }
+}
+
+/// 스트리밍 파일이 생겼는지 확인하는 간격
+const LIVE_START_POLL: Duration = Duration::from_millis(250);
+
+/// 다운로드 중인 영상을 받는 대로 스트리밍 (/video/request?live=true로 시작)
+/// GET /video/live/{key}  (key는 캐시 키, /video/request 응답의 URL을 그대로 사용)
+///
+/// 다운로드가 끝난 뒤에는 /video/files로 리다이렉트
+/// 스트리밍 파일을 만들지 않는 다운로드(같은 키의 일반 요청)에 합류했으면 완료될 때까지 기다렸다가 리다이렉트
+async fn handle_video_live(
+    State(coordinator): State<Arc<DownloadCoordinator>>,
+    Path(cache_key): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    if !is_safe_cache_key(&cache_key) {
+        return bad_request(&cache_key, "Invalid video key".to_string());
+    }
+    let video_id = DownloadRequest::from_cache_key(&cache_key).video_id;
+    let video_id = video_id.as_str();
+    let ytdlp = &coordinator.ytdlp;
+
+    loop {
+        let progress = coordinator.subscribe_in_progress(&cache_key).await;
+
+        if let Some(video_path) = ytdlp.cached_file(&cache_key) {
+            let file_name = video_path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or_default();
+            return Redirect::temporary(&format!("/video/files/{}", file_name)).into_response();
+        }
+
+        let Some(progress) = progress else {
+            return (
+                StatusCode::NOT_FOUND,
+                axum::Json(VideoResponse {
+                    success: false,
+                    video_id: video_id.to_string(),
+                    url: None,
+                    message: Some("Video is not downloading".to_string()),
+                    sync: None,
+                    error_code: None,
+                    retry_after: None,
+                }),
+            )
+                .into_response();
+        };
+
+        if let Some(live_path) = ytdlp.live_file(&cache_key) {
+            return live_stream::serve_growing_file(&live_path, &headers, progress).await;
+        }
+
+        tokio::time::sleep(LIVE_START_POLL).await;
     }
 }
 
+/// 썸네일/스프라이트 요청 실패 응답
+fn thumbnail_error(video_id: &str, status: StatusCode, message: String) -> Response {
+    (
+        status,
+        axum::Json(VideoResponse {
+            success: false,
+            video_id: video_id.to_string(),
+            url: None,
+            message: Some(message),
+            sync: None,
+            error_code: None,
+            retry_after: None,
+        }),
+    )
+        .into_response()
+}
+
+/// 이미지 파일 응답 (생성된 썸네일은 바뀌지 않으므로 브라우저 캐시 허용)
+async fn image_response(path: &std::path::Path) -> Response {
+    match tokio::fs::read(path).await {
+        Ok(bytes) => (
+            [
+                (axum::http::header::CONTENT_TYPE, "image/jpeg"),
+                (axum::http::header::CACHE_CONTROL, "max-age=86400"),
+            ],
+            bytes,
+        )
+            .into_response(),
+        Err(e) => (StatusCode::NOT_FOUND, e.to_string()).into_response(),
+    }
+}
+
+/// 캐시된 영상의 썸네일
+/// GET /video/thumbs/{id}  (YouTube는 영상 id, 다른 출처는 `{source}~{id}` 형식의 키)
+async fn handle_video_thumbnail(
+    State(coordinator): State<Arc<DownloadCoordinator>>,
+    Path(key_id): Path<String>,
+) -> Response {
+    let key_id = key_id.trim();
+    if !is_safe_cache_key(key_id) || key_id.contains('.') {
+        return bad_request(key_id, "Invalid video ID".to_string());
+    }
+    // 캐시에 없는 id로 외부 썸네일을 받아 두지 않도록 캐시된 영상만 처리
+    if !coordinator.is_cached_video(key_id) {
+        return thumbnail_error(
+            key_id,
+            StatusCode::NOT_FOUND,
+            "Video not in cache".to_string(),
+        );
+    }
+
+    let video = coordinator.video_file_for(key_id);
+    let result = coordinator
+        .thumbnails
+        .thumbnail(
+            key_id,
+            video.as_ref().map(|(path, _)| path.as_path()),
+            video.as_ref().and_then(|(_, duration)| *duration),
+        )
+        .await;
+
+    match result {
+        Ok(path) => image_response(&path).await,
+        Err(e) => thumbnail_error(key_id, StatusCode::NOT_FOUND, e),
+    }
+}
+
+/// 스프라이트 생성에 필요한 영상 파일과 길이 확인 후 생성
+async fn ensure_sprite(
+    coordinator: &DownloadCoordinator,
+    key_id: &str,
+) -> Result<SpriteInfo, Response> {
+    if !is_safe_cache_key(key_id) || key_id.contains('.') {
+        return Err(bad_request(key_id, "Invalid video ID".to_string()));
+    }
+
+    let Some((video_file, duration)) = coordinator.video_file_for(key_id) else {
+        return Err(thumbnail_error(
+            key_id,
+            StatusCode::NOT_FOUND,
+            "Video not downloaded".to_string(),
+        ));
+    };
+    let Some(duration) = duration.filter(|d| *d > 0.0) else {
+        return Err(thumbnail_error(
+            key_id,
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "Unknown video duration".to_string(),
+        ));
+    };
+
+    coordinator
+        .thumbnails
+        .sprite(key_id, &video_file, duration)
+        .await
+        .map_err(|e| thumbnail_error(key_id, StatusCode::SERVICE_UNAVAILABLE, e))
+}
+
+/// 미리보기 스프라이트 이미지 (ffmpeg 필요, 처음 요청 시 생성)
+/// GET /video/thumbs/{id}/sprite
+async fn handle_video_sprite(
+    State(coordinator): State<Arc<DownloadCoordinator>>,
+    Path(key_id): Path<String>,
+) -> Response {
+    let key_id = key_id.trim();
+    match ensure_sprite(&coordinator, key_id).await {
+        Ok(_) => image_response(&coordinator.thumbnails.sprite_path(key_id)).await,
+        Err(response) => response,
+    }
+}
+
+/// 미리보기 스프라이트 배치 정보 (칸 크기, 열/행 수, 칸 사이 간격)
+/// GET /video/thumbs/{id}/sprite.json
+async fn handle_video_sprite_info(
+    State(coordinator): State<Arc<DownloadCoordinator>>,
+    Path(key_id): Path<String>,
+) -> Response {
+    match ensure_sprite(&coordinator, key_id.trim()).await {
+        Ok(info) => Json(info).into_response(),
+        Err(response) => response,
+    }
+}
+
+/// 메타데이터 조회 쿼리
+#[derive(serde::Deserialize)]
+struct InfoQuery {
+    #[serde(default)]
+    source: VideoSource,
+    id: String,
+    /// true면 캐시를 무시하고 다시 조회
+    #[serde(default)]
+    refresh: bool,
+}
+
+/// 메타데이터 응답
+#[derive(serde::Serialize)]
+struct InfoResponse {
+    success: bool,
+    video_id: String,
+    info: Option<VideoInfo>,
+    message: Option<String>,
+}
+
+/// 다운로드 없이 비디오 메타데이터 반환 (TTL 동안 캐시)
+/// GET /video/info?id=<youtube_id>
+async fn handle_video_info(
+    State(coordinator): State<Arc<DownloadCoordinator>>,
+    Query(query): Query<InfoQuery>,
+) -> Response {
+    let video_id = query.id.trim();
+
+    if let Err(e) = query.source.validate_id(video_id) {
+        return bad_request(video_id, e);
+    }
+
+    match coordinator
+        .video_info(query.source, video_id, query.refresh)
+        .await
+    {
+        Ok(info) => axum::Json(InfoResponse {
+            success: true,
+            video_id: video_id.to_string(),
+            info: Some(info),
+            message: None,
+        })
+        .into_response(),
+        Err(e) => (
+            StatusCode::BAD_GATEWAY,
+            axum::Json(InfoResponse {
+                success: false,
+                video_id: video_id.to_string(),
+                info: None,
+                message: Some(e),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+/// 트랙 검색 쿼리
+#[derive(serde::Deserialize)]
+struct SearchQuery {
+    title: String,
+    #[serde(default)]
+    artist: Option<String>,
+    /// 트랙 길이 (ms, TrackInfo.duration과 같은 단위)
+    #[serde(default)]
+    duration: Option<u64>,
+    /// Spotify URI (저장된 매핑 조회용)
+    #[serde(default)]
+    uri: Option<String>,
+    /// 검색할 후보 수 (기본 5, 최대 20)
+    #[serde(default)]
+    limit: Option<usize>,
+    /// true면 가장 점수가 높은 영상을 백그라운드로 다운로드
+    #[serde(default)]
+    auto: bool,
+}
+
+/// 트랙 검색 응답
+#[derive(serde::Serialize)]
+struct SearchResponse {
+    success: bool,
+    query: String,
+    results: Vec<SearchCandidate>,
+    /// 사용자가 지정한 매핑 (있으면 auto=true일 때 검색 결과보다 우선)
+    mapping: Option<TrackMapping>,
+    /// auto=true일 때 다운로드를 시작한 비디오 id
+    requested: Option<String>,
+    message: Option<String>,
+}
+
+const DEFAULT_SEARCH_LIMIT: usize = 5;
+const MAX_SEARCH_LIMIT: usize = 20;
+
+/// 트랙 정보로 YouTube 영상 후보를 찾아 점수순으로 반환
+/// GET /video/search?title=<title>&artist=<artist>&duration=<ms>&limit=<n>&auto=<bool>
+///
+/// 제목 유사도, 공식 채널 여부, 트랙 길이와의 차이로 점수를 매김
+async fn handle_video_search(
+    State(coordinator): State<Arc<DownloadCoordinator>>,
+    Query(query): Query<SearchQuery>,
+) -> Response {
+    let title = query.title.trim();
+    let artist = query
+        .artist
+        .as_deref()
+        .map(str::trim)
+        .filter(|a| !a.is_empty());
+    let track = SearchTrack {
+        title,
+        artist,
+        duration_ms: query.duration,
+    };
+    let search_query = track.query();
+
+    if title.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            axum::Json(SearchResponse {
+                success: false,
+                query: search_query,
+                results: Vec::new(),
+                mapping: None,
+                requested: None,
+                message: Some("Missing title".to_string()),
+            }),
+        )
+            .into_response();
+    }
+
+    let uri = query
+        .uri
+        .as_deref()
+        .map(str::trim)
+        .filter(|u| !u.is_empty());
+    let mapping = coordinator
+        .mappings
+        .lookup(title, artist, uri)
+        .filter(|m| m.source == MappingSource::User);
+
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT);
+    let entries = match coordinator.ytdlp.search_videos(&search_query, limit).await {
+        Ok(entries) => entries,
+        Err(e) => {
+            return (
+                StatusCode::BAD_GATEWAY,
+                axum::Json(SearchResponse {
+                    success: false,
+                    query: search_query,
+                    results: Vec::new(),
+                    mapping: None,
+                    requested: None,
+                    message: Some(e.to_string()),
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    let mut results: Vec<SearchCandidate> = entries
+        .iter()
+        .filter_map(|entry| track.score(entry))
+        .collect();
+    results.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+    let mut requested = None;
+    if query.auto {
+        let video = mapping
+            .as_ref()
+            .map(|m| (m.video_source, m.video_id.clone()))
+            .or_else(|| {
+                results
+                    .first()
+                    .map(|best| (VideoSource::Youtube, best.video_id.clone()))
+            });
+        if let Some((source, video_id)) = video {
+            let request = DownloadRequest {
+                source,
+                video_id: video_id.clone(),
+                mode: DownloadMode::Video,
+                clip: None,
+                track: Some(RequestedTrack {
+                    title: title.to_string(),
+                    artist: artist.map(|a| a.to_string()),
+                    album: None,
+                    duration: query.duration,
+                    uri: uri.map(|u| u.to_string()),
+                }),
+                live: false,
+            };
+            if coordinator
+                .ytdlp
+                .cached_file(&request.cache_key())
+                .is_none()
+            {
+                // 진행 상황은 /video/request나 /video/status로 확인
+                let _ = coordinator.start_or_subscribe(request).await;
+            }
+            requested = Some(video_id);
+        }
+    }
+
+    axum::Json(SearchResponse {
+        success: true,
+        query: search_query,
+        results,
+        mapping,
+        requested,
+        message: None,
+    })
+    .into_response()
+}
+
+/// 재생목록 쿼리
+#[derive(serde::Deserialize)]
+struct PlaylistQuery {
+    /// 재생목록 id 또는 `list=`가 포함된 재생목록 URL
+    id: String,
+    #[serde(default)]
+    mode: DownloadMode,
+}
+
+/// 재생목록 응답
+#[derive(serde::Serialize)]
+struct PlaylistResponse {
+    success: bool,
+    playlist: Option<PlaylistProgress>,
+    message: Option<String>,
+}
+
+impl PlaylistResponse {
+    fn error(status: StatusCode, message: String) -> Response {
+        (
+            status,
+            Json(Self {
+                success: false,
+                playlist: None,
+                message: Some(message),
+            }),
+        )
+            .into_response()
+    }
+}
+
+/// YouTube 재생목록의 영상을 모두 백그라운드로 미리 다운로드
+/// GET /video/playlist?id=<playlist_id|url>&mode=<video|audio|muxed>
+///
+/// 캐시 용량 한도에 닿으면 남은 영상은 건너뜀, 진행 상황은 /video/playlist/status로 확인
+async fn handle_video_playlist(
+    State(coordinator): State<Arc<DownloadCoordinator>>,
+    Query(query): Query<PlaylistQuery>,
+) -> Response {
+    let playlist_id = match playlist::parse_playlist_id(&query.id) {
+        Ok(playlist_id) => playlist_id,
+        Err(e) => return PlaylistResponse::error(StatusCode::BAD_REQUEST, e),
+    };
+
+    match coordinator.start_playlist(&playlist_id, query.mode).await {
+        Ok(progress) => Json(PlaylistResponse {
+            success: true,
+            playlist: Some(progress),
+            message: None,
+        })
+        .into_response(),
+        Err(e) => PlaylistResponse::error(StatusCode::BAD_GATEWAY, e),
+    }
+}
+
+/// 재생목록 일괄 다운로드 진행 상황
+/// GET /video/playlist/status?id=<playlist_id|url>
+async fn handle_playlist_status(
+    State(coordinator): State<Arc<DownloadCoordinator>>,
+    Query(query): Query<PlaylistQuery>,
+) -> Response {
+    let playlist_id = match playlist::parse_playlist_id(&query.id) {
+        Ok(playlist_id) => playlist_id,
+        Err(e) => return PlaylistResponse::error(StatusCode::BAD_REQUEST, e),
+    };
+
+    match coordinator.playlist_progress(&playlist_id) {
+        Some(progress) => Json(PlaylistResponse {
+            success: true,
+            playlist: Some(progress),
+            message: None,
+        })
+        .into_response(),
+        None => PlaylistResponse::error(
+            StatusCode::NOT_FOUND,
+            "Playlist download not started".to_string(),
+        ),
+    }
+}
+
+/// 매핑 목록 쿼리
+#[derive(serde::Deserialize)]
+struct MappingListQuery {
+    /// 제목/아티스트/앨범/비디오 id 검색어
+    #[serde(default)]
+    q: Option<String>,
+}
+
+/// 매핑 조회/삭제 응답
+#[derive(serde::Serialize)]
+struct MappingResponse {
+    success: bool,
+    mapping: Option<TrackMapping>,
+    message: Option<String>,
+}
+
+impl MappingResponse {
+    fn error(status: StatusCode, message: String) -> Response {
+        (
+            status,
+            Json(Self {
+                success: false,
+                mapping: None,
+                message: Some(message),
+            }),
+        )
+            .into_response()
+    }
+}
+
+/// 저장된 트랙-영상 매핑 목록 (최근 수정순)
+/// GET /video/mappings?q=<검색어>
+async fn handle_list_mappings(
+    State(coordinator): State<Arc<DownloadCoordinator>>,
+    Query(query): Query<MappingListQuery>,
+) -> Json<Vec<TrackMapping>> {
+    Json(coordinator.mappings.list(query.q.as_deref()))
+}
+
+/// 사용자 매핑 저장 (자동 기록보다 우선)
+/// POST /video/mappings  { title, artist, album, duration, uri, video_id, offset_ms }
+async fn handle_save_mapping(
+    State(coordinator): State<Arc<DownloadCoordinator>>,
+    Json(input): Json<TrackMappingInput>,
+) -> Response {
+    match coordinator.mappings.upsert(input) {
+        Ok(mapping) => Json(MappingResponse {
+            success: true,
+            mapping: Some(mapping),
+            message: None,
+        })
+        .into_response(),
+        Err(e) => MappingResponse::error(StatusCode::BAD_REQUEST, e),
+    }
+}
+
+/// 매핑 삭제 쿼리
+#[derive(serde::Deserialize)]
+struct MappingKeyQuery {
+    key: String,
+}
+
+/// 매핑 삭제
+/// DELETE /video/mappings?key=<매핑 키>
+async fn handle_delete_mapping(
+    State(coordinator): State<Arc<DownloadCoordinator>>,
+    Query(query): Query<MappingKeyQuery>,
+) -> Response {
+    if coordinator.mappings.remove(&query.key) {
+        Json(MappingResponse {
+            success: true,
+            mapping: None,
+            message: None,
+        })
+        .into_response()
+    } else {
+        MappingResponse::error(StatusCode::NOT_FOUND, "Mapping not found".to_string())
+    }
+}
+
+/// 매핑 단건 조회 쿼리
+#[derive(serde::Deserialize)]
+struct MappingLookupQuery {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    artist: Option<String>,
+    #[serde(default)]
+    uri: Option<String>,
+}
+
+/// 트랙에 연결된 영상 조회 (URI 우선, 없으면 아티스트/제목)
+/// GET /video/mappings/lookup?title=<title>&artist=<artist>&uri=<spotify uri>
+async fn handle_lookup_mapping(
+    State(coordinator): State<Arc<DownloadCoordinator>>,
+    Query(query): Query<MappingLookupQuery>,
+) -> Response {
+    let artist = query
+        .artist
+        .as_deref()
+        .map(str::trim)
+        .filter(|a| !a.is_empty());
+    match coordinator
+        .mappings
+        .lookup(query.title.trim(), artist, query.uri.as_deref())
+    {
+        Some(mapping) => Json(MappingResponse {
+            success: true,
+            mapping: Some(mapping),
+            message: None,
+        })
+        .into_response(),
+        None => MappingResponse::error(StatusCode::NOT_FOUND, "Mapping not found".to_string()),
+    }
+}
+
+/// 캐시 카탈로그 조회 엔드포인트
+/// GET /video/library?q=&uploader=&sort=&order=&offset=&limit=
+async fn handle_video_library(
+    State(coordinator): State<Arc<DownloadCoordinator>>,
+    Query(query): Query<LibraryQuery>,
+) -> axum::Json<LibraryPage> {
+    let ytdlp = &coordinator.ytdlp;
+    let library = ytdlp.library();
+    library.reconcile(&ytdlp.videos_dir());
+    axum::Json(library.list(&query))
+}
+
+/// yt-dlp 버전 쿼리
+#[derive(serde::Deserialize)]
+struct YtDlpVersionQuery {
+    /// true면 저장된 결과 대신 지금 다시 확인
+    #[serde(default)]
+    refresh: bool,
+}
+
+/// 설치된 yt-dlp와 최신 릴리스 버전
+/// GET /video/ytdlp?refresh=<bool>
+async fn handle_ytdlp_version(
+    State(coordinator): State<Arc<DownloadCoordinator>>,
+    Query(query): Query<YtDlpVersionQuery>,
+) -> Json<YtDlpVersion> {
+    let ytdlp = &coordinator.ytdlp;
+    let version = ytdlp.ytdlp_version();
+    if query.refresh || version.checked_at.is_none() {
+        Json(ytdlp.check_ytdlp_version().await)
+    } else {
+        Json(version)
+    }
+}
+
+/// yt-dlp/Deno/인스톨러 다운로드 진행 상황 (받은 적이 없으면 null)
+/// GET /video/ytdlp/progress
+async fn handle_ytdlp_download_progress(
+    State(coordinator): State<Arc<DownloadCoordinator>>,
+) -> Json<Option<BinaryDownloadProgress>> {
+    Json(coordinator.ytdlp.binary_downloads().latest())
+}
+
+/// /video/files 응답이 성공하면 해당 비디오의 마지막 접근 시각 갱신 (LRU 캐시 정리용)
+async fn record_file_access(
+    State(coordinator): State<Arc<DownloadCoordinator>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    // 비디오 폴더 바로 아래 파일 하나만 허용 (하위 경로, `..`, 인코딩된 구분자 거부)
+    let file_name = request
+        .uri()
+        .path()
+        .strip_prefix("/video/files/")
+        .filter(|file_name| is_safe_cache_key(file_name))
+        .map(|file_name| file_name.to_string());
+    let Some(file_name) = file_name else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let cache_key = std::path::Path::new(&file_name)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .map(|stem| stem.to_string());
+
+    let response = next.run(request).await;
+
+    if let Some(cache_key) = cache_key {
+        if response.status().is_success() {
+            let ytdlp = &coordinator.ytdlp;
+            if !ytdlp.library().touch(&cache_key) {
+                // 카탈로그에 없는 기존 파일이면 동기화 후 다시 기록
+                ytdlp.library().reconcile(&ytdlp.videos_dir());
+                ytdlp.library().touch(&cache_key);
+            }
+        }
+    }
+
+    response
+}
+
+/// SSE 이벤트 데이터 (완료 시 싱크 힌트 포함)
+#[derive(serde::Serialize)]
+struct ProgressEvent {
+    #[serde(flatten)]
+    progress: DownloadProgress,
+    #[serde(flatten)]
+    sync: Option<SyncHint>,
+}
+
 /// broadcast 수신기를 SSE 스트림으로 변환
 fn create_progress_stream(
     rx: broadcast::Receiver<DownloadProgress>,
+    coordinator: Arc<DownloadCoordinator>,
+    request: DownloadRequest,
 ) -> impl Stream<Item = Result<Event, Infallible>> {
     let stream = BroadcastStream::new(rx);
 
-    stream.filter_map(|result| match result {
+    stream.filter_map(move |result| match result {
         Ok(progress) => {
-            let is_final = progress.status == DownloadStatus::Completed
-                || progress.status == DownloadStatus::Error
+            let is_available = progress.status == DownloadStatus::Completed
                 || progress.status == DownloadStatus::AlreadyExists;
+            let is_final = is_available || progress.status == DownloadStatus::Error;
 
-            let event_data = serde_json::to_string(&progress).unwrap_or_default();
+            let sync = is_available.then(|| coordinator.sync_hint(&request));
+            let event_data =
+                serde_json::to_string(&ProgressEvent { progress, sync }).unwrap_or_default();
             let event = Event::default().data(event_data).event(if is_final {
                 "complete"
             } else {
@@ -183,56 +1100,622 @@ fn create_progress_stream(
 /// 진행 중 다운로드를 공유하기 위한 코디네이터
 pub struct DownloadCoordinator {
     ytdlp: YtDlpManager,
+    mappings: TrackMappingStore,
+    thumbnails: ThumbnailStore,
     in_progress: Arc<Mutex<HashMap<String, broadcast::Sender<DownloadProgress>>>>,
+    /// /video/info 결과 캐시 (조회 시각, 결과)
+    info_cache: Mutex<HashMap<String, (Instant, VideoInfo)>>,
+    /// 진행 중인 일반(요청) 다운로드 수
+    foreground_active: Arc<AtomicUsize>,
+    /// 백그라운드 다운로드는 한 번에 하나씩
+    background_slot: Arc<Semaphore>,
+    /// 재생목록 일괄 다운로드 진행 상황 (재생목록 id별)
+    playlists: std::sync::Mutex<HashMap<String, PlaylistProgress>>,
+    /// 최근 실패한 다운로드 (캐시 키별, 원인에 따라 기간이 지나면 다시 시도)
+    /// 모드/구간에 따라 실패 원인이 달라서 영상 단위로 묶지 않음
+    /// (라이브 여부는 같은 파일을 받는 방식만 다르므로 구분하지 않음)
+    failures: Arc<std::sync::Mutex<HashMap<String, FailedDownload>>>,
 }
 
+/// 실패한 다운로드 기록
+#[derive(Clone, Debug)]
+struct FailedDownload {
+    code: DownloadErrorCode,
+    message: String,
+    expires_at: Instant,
+}
+
+impl FailedDownload {
+    fn retry_after(&self) -> Duration {
+        self.expires_at.saturating_duration_since(Instant::now())
+    }
+}
+
+/// 메타데이터 캐시 유지 시간
+const INFO_CACHE_TTL: Duration = Duration::from_secs(30 * 60);
+/// 백그라운드 다운로드가 일반 다운로드 종료를 기다리는 간격
+const BACKGROUND_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
 impl DownloadCoordinator {
-    pub fn new(ytdlp: YtDlpManager) -> Self {
+    pub fn new(ytdlp: YtDlpManager, mappings: TrackMappingStore) -> Self {
+        let thumbnails = ThumbnailStore::new(&ytdlp);
         Self {
             ytdlp,
+            mappings,
+            thumbnails,
             in_progress: Arc::new(Mutex::new(HashMap::new())),
+            info_cache: Mutex::new(HashMap::new()),
+            foreground_active: Arc::new(AtomicUsize::new(0)),
+            background_slot: Arc::new(Semaphore::new(1)),
+            playlists: std::sync::Mutex::new(HashMap::new()),
+            failures: Arc::new(std::sync::Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// 같은 캐시 키(영상/모드/구간)의 아직 만료되지 않은 실패 기록
+    fn known_failure(&self, request: &DownloadRequest) -> Option<FailedDownload> {
+        let mut failures = self.failures.lock().ok()?;
+        failures.retain(|_, failure| failure.expires_at > Instant::now());
+        failures.get(&request.cache_key()).cloned()
+    }
+
+    fn forget_failure(&self, request: &DownloadRequest) {
+        if let Ok(mut failures) = self.failures.lock() {
+            failures.remove(&request.cache_key());
+        }
+    }
+
+    /// 캐시된 메타데이터가 유효하면 반환하고, 아니면 yt-dlp로 조회
+    pub async fn video_info(
+        &self,
+        source: VideoSource,
+        video_id: &str,
+        refresh: bool,
+    ) -> Result<VideoInfo, String> {
+        let cache_key = source.key_id(video_id);
+        if !refresh {
+            if let Some((fetched_at, info)) = self.info_cache.lock().await.get(&cache_key) {
+                if fetched_at.elapsed() < INFO_CACHE_TTL {
+                    return Ok(info.clone());
+                }
+            }
+        }
+
+        let info = self
+            .ytdlp
+            .fetch_video_info(source, video_id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut cache = self.info_cache.lock().await;
+        cache.retain(|_, (fetched_at, _)| fetched_at.elapsed() < INFO_CACHE_TTL);
+        cache.insert(cache_key, (Instant::now(), info.clone()));
+
+        Ok(info)
+    }
+
+    /// 요청한 영상을 트랙에 맞추기 위한 힌트
+    /// 요청의 트랙 정보로 찾은 매핑 → 영상에 연결된 매핑 → 길이 비교 순
+    fn sync_hint(&self, request: &DownloadRequest) -> SyncHint {
+        let mapping = request
+            .track
+            .as_ref()
+            .and_then(|t| {
+                self.mappings
+                    .lookup(&t.title, t.artist.as_deref(), t.uri.as_deref())
+            })
+            .filter(|m| m.video_source == request.source && m.video_id == request.video_id)
+            .or_else(|| self.mappings.for_video(request.source, &request.video_id));
+
+        let entry = self.ytdlp.library().get(&request.cache_key());
+        let track_ms = request
+            .track
+            .as_ref()
+            .and_then(|t| t.duration)
+            .or_else(|| entry.as_ref()?.track.as_ref()?.duration);
+
+        SyncHint::resolve(
+            mapping.as_ref(),
+            entry.and_then(|e| e.duration),
+            track_ms,
+            request.clip.as_ref(),
+        )
+    }
+
+    /// 캐시에 있는 영상인지 (모드/구간 무관)
+    fn is_cached_video(&self, key_id: &str) -> bool {
+        let library = self.ytdlp.library();
+        if library.entries_for_video(key_id).is_empty() {
+            library.reconcile(&self.ytdlp.videos_dir());
         }
+        !library.entries_for_video(key_id).is_empty()
+    }
+
+    /// 썸네일/스프라이트를 만들 영상 파일과 길이 (영상이 있는 전체 다운로드 우선)
+    fn video_file_for(&self, key_id: &str) -> Option<(std::path::PathBuf, Option<f64>)> {
+        let library = self.ytdlp.library();
+        let mut entries = library.entries_for_video(key_id);
+        if entries.is_empty() {
+            library.reconcile(&self.ytdlp.videos_dir());
+            entries = library.entries_for_video(key_id);
+        }
+        entries.sort_by_key(|entry| entry.clip.is_some());
+
+        entries
+            .into_iter()
+            .filter(|entry| entry.mode != DownloadMode::Audio)
+            .find_map(|entry| {
+                let path = self.ytdlp.videos_dir().join(&entry.file_name);
+                path.is_file().then_some((path, entry.duration))
+            })
+    }
+
+    /// 진행 중인 다운로드가 있으면 진행 채널 구독
+    async fn subscribe_in_progress(
+        &self,
+        cache_key: &str,
+    ) -> Option<broadcast::Receiver<DownloadProgress>> {
+        self.in_progress
+            .lock()
+            .await
+            .get(cache_key)
+            .map(|sender| sender.subscribe())
     }
 
     /// 이미 진행 중이면 기존 SSE 스트림에 합류하고, 아니면 새 다운로드를 시작
+    /// 같은 영상이라도 모드가 다르면 캐시 키가 달라 별도 다운로드로 취급
     pub async fn start_or_subscribe(
         &self,
-        video_id: &str,
+        request: DownloadRequest,
+    ) -> broadcast::Receiver<DownloadProgress> {
+        self.spawn_download(request, true).await
+    }
+
+    /// 낮은 우선순위로 다운로드 예약
+    /// 백그라운드 작업은 하나씩 순서대로, 일반 다운로드가 없을 때만 시작
+    pub fn enqueue_background(self: &Arc<Self>, request: DownloadRequest) {
+        let coordinator = self.clone();
+        tokio::spawn(async move {
+            let _ = coordinator.download_in_background(request, |_| {}).await;
+        });
+    }
+
+    /// 백그라운드 슬롯을 얻어 다운로드하고 끝날 때까지 대기
+    ///
+    /// 새로 받았으면 Ok(true), 이미 캐시에 있었으면 Ok(false)
+    async fn download_in_background(
+        &self,
+        request: DownloadRequest,
+        mut on_progress: impl FnMut(&DownloadProgress),
+    ) -> Result<bool, String> {
+        let _permit = self
+            .background_slot
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        while self.foreground_active.load(Ordering::SeqCst) > 0 {
+            tokio::time::sleep(BACKGROUND_POLL_INTERVAL).await;
+        }
+
+        let cache_key = request.cache_key();
+        if self.ytdlp.cached_file(&cache_key).is_some() {
+            return Ok(false);
+        }
+
+        // 다운로드가 끝날 때까지 슬롯을 잡고 있음
+        let mut rx = self.spawn_download(request, false).await;
+        let mut error = None;
+        loop {
+            match rx.recv().await {
+                Ok(progress) => {
+                    if progress.status == DownloadStatus::Error {
+                        error = progress.message.clone();
+                    }
+                    on_progress(&progress);
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+
+        if self.ytdlp.cached_file(&cache_key).is_some() {
+            Ok(true)
+        } else {
+            Err(error.unwrap_or_else(|| "Download failed".to_string()))
+        }
+    }
+
+    /// 재생목록 영상을 모두 백그라운드 다운로드 대기열에 추가
+    /// 같은 재생목록을 이미 받는 중이면 현재 진행 상황만 반환
+    pub async fn start_playlist(
+        self: &Arc<Self>,
+        playlist_id: &str,
+        mode: DownloadMode,
+    ) -> Result<PlaylistProgress, String> {
+        if let Some(progress) = self
+            .playlist_progress(playlist_id)
+            .filter(PlaylistProgress::is_running)
+        {
+            return Ok(progress);
+        }
+
+        let entries = self
+            .ytdlp
+            .playlist_entries(playlist_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        let progress = PlaylistProgress::new(playlist_id, mode, &entries);
+        if progress.items.is_empty() {
+            return Err("Playlist has no videos".to_string());
+        }
+
+        if let Ok(mut playlists) = self.playlists.lock() {
+            playlists.insert(playlist_id.to_string(), progress.clone());
+        }
+        let coordinator = self.clone();
+        let playlist_id = playlist_id.to_string();
+        tokio::spawn(async move { coordinator.run_playlist(&playlist_id).await });
+
+        Ok(progress)
+    }
+
+    pub fn playlist_progress(&self, playlist_id: &str) -> Option<PlaylistProgress> {
+        self.playlists.lock().ok()?.get(playlist_id).cloned()
+    }
+
+    fn update_playlist(&self, playlist_id: &str, update: impl FnOnce(&mut PlaylistProgress)) {
+        if let Ok(mut playlists) = self.playlists.lock() {
+            if let Some(progress) = playlists.get_mut(playlist_id) {
+                update(progress);
+            }
+        }
+    }
+
+    /// 재생목록 순서대로 하나씩 다운로드 (캐시 용량이 차면 중단)
+    ///
+    /// 진행 중에는 재생목록의 영상을 캐시 정리에서 제외해 앞서 받은 영상이 지워지지 않게 함
+    async fn run_playlist(&self, playlist_id: &str) {
+        let Some(progress) = self.playlist_progress(playlist_id) else {
+            return;
+        };
+        let owner = format!("playlist:{}", playlist_id);
+        let cache_keys = progress
+            .items
+            .iter()
+            .map(|item| playlist_request(&item.video_id, progress.mode).cache_key())
+            .collect();
+        self.ytdlp.exempt_from_pruning(&owner, cache_keys);
+
+        let state = self.download_playlist_items(playlist_id, &progress).await;
+
+        self.ytdlp.end_pruning_exemption(&owner);
+        self.update_playlist(playlist_id, |p| p.finish(state));
+    }
+
+    async fn download_playlist_items(
+        &self,
+        playlist_id: &str,
+        progress: &PlaylistProgress,
+    ) -> PlaylistState {
+        let max_bytes = self.ytdlp.max_cache_bytes().await;
+
+        for (index, item) in progress.items.iter().enumerate() {
+            let request = playlist_request(&item.video_id, progress.mode);
+
+            // 받은 뒤 용량을 넘길 것으로 보이면 여기서 멈춤 (넘기면 앞서 받은 영상이 정리됨)
+            let cached = self.ytdlp.cached_file(&request.cache_key()).is_some();
+            if !cached && max_bytes > 0 {
+                let projected = self
+                    .ytdlp
+                    .cache_usage()
+                    .await
+                    .saturating_add(item.estimated_bytes.unwrap_or(0));
+                if projected >= max_bytes {
+                    tracing::info!(
+                        "Cache budget would be exceeded by {}, skipping the rest of playlist {}",
+                        item.video_id,
+                        playlist_id
+                    );
+                    return PlaylistState::BudgetExceeded;
+                }
+            }
+
+            self.update_playlist(playlist_id, |p| {
+                p.set_item(index, PlaylistItemStatus::Downloading, None, None)
+            });
+            let result = self
+                .download_in_background(request, |download| {
+                    if download.percent.is_some() {
+                        self.update_playlist(playlist_id, |p| {
+                            p.set_item(
+                                index,
+                                PlaylistItemStatus::Downloading,
+                                download.percent,
+                                None,
+                            )
+                        });
+                    }
+                })
+                .await;
+
+            let (status, message) = match result {
+                Ok(true) => (PlaylistItemStatus::Completed, None),
+                Ok(false) => (PlaylistItemStatus::Cached, None),
+                Err(e) => (PlaylistItemStatus::Failed, Some(e)),
+            };
+            self.update_playlist(playlist_id, |p| p.set_item(index, status, None, message));
+        }
+
+        PlaylistState::Completed
+    }
+
+    async fn spawn_download(
+        &self,
+        request: DownloadRequest,
+        foreground: bool,
     ) -> broadcast::Receiver<DownloadProgress> {
+        let cache_key = request.cache_key();
+        let mut in_progress = self.in_progress.lock().await;
+
         // 이미 진행 중인 다운로드가 있으면 해당 채널에 합류
-        if let Some(sender) = self.in_progress.lock().await.get(video_id) {
+        if let Some(sender) = in_progress.get(&cache_key) {
             return sender.subscribe();
         }
 
+        // 최근 실패한 영상은 프리페치/재생목록에서도 다시 받지 않고 실패만 전달
+        if let Some(failure) = self.known_failure(&request) {
+            let (tx, rx) = broadcast::channel::<DownloadProgress>(1);
+            let _ = tx.send(DownloadProgress {
+                video_id: request.video_id.clone(),
+                status: DownloadStatus::Error,
+                percent: None,
+                speed: None,
+                eta: None,
+                transfer: None,
+                error_code: Some(failure.code),
+                message: Some(failure.message),
+            });
+            return rx;
+        }
+
         // 새 다운로드 채널 생성
         let (tx, rx) = broadcast::channel::<DownloadProgress>(100);
-        self.in_progress
-            .lock()
-            .await
-            .insert(video_id.to_string(), tx.clone());
+        in_progress.insert(cache_key.clone(), tx.clone());
+        drop(in_progress);
 
         // 다운로드 작업 시작
-        let video_id_owned = video_id.to_string();
         let ytdlp = self.ytdlp.clone();
+        let thumbnails = self.thumbnails.clone();
         let in_progress = self.in_progress.clone();
+        let failures = self.failures.clone();
+        let foreground_active = self.foreground_active.clone();
+        if foreground {
+            foreground_active.fetch_add(1, Ordering::SeqCst);
+        }
         tokio::spawn(async move {
-            let result = ytdlp.download_video(&video_id_owned, tx.clone()).await;
+            let started_at = SystemTime::now();
+            let result = ytdlp.download_video(&request, tx.clone()).await;
 
-            if let Err(e) = result {
+            if let Err(e) = &result {
                 let _ = tx.send(DownloadProgress {
-                    video_id: video_id_owned.clone(),
+                    video_id: request.video_id.clone(),
                     status: DownloadStatus::Error,
                     percent: None,
                     speed: None,
                     eta: None,
+                    transfer: None,
+                    error_code: Some(e.code),
                     message: Some(e.to_string()),
                 });
             }
 
-            // 다운로드가 끝났으니 in-progress 목록에서 제거
-            in_progress.lock().await.remove(&video_id_owned);
+            // 비공개/삭제 등 다시 시도해도 소용없는 실패는 일정 기간 기억
+            if let Ok(mut failures) = failures.lock() {
+                match &result {
+                    Ok(_) => {
+                        failures.remove(&cache_key);
+                    }
+                    Err(e) => {
+                        if let Some(ttl) = e.negative_cache_ttl() {
+                            failures.insert(
+                                cache_key.clone(),
+                                FailedDownload {
+                                    code: e.code,
+                                    message: e.to_string(),
+                                    expires_at: Instant::now() + ttl,
+                                },
+                            );
+                        }
+                    }
+                }
+            }
+
+            // 다운로드가 끝났으니 in-progress 목록에서 제거하고 채널을 닫음
+            // (썸네일 생성을 기다리지 않고 /video/live 스트림이 끝나도록)
+            in_progress.lock().await.remove(&cache_key);
+            drop(tx);
+            if foreground {
+                foreground_active.fetch_sub(1, Ordering::SeqCst);
+            }
+
+            // 캐시 브라우저용 썸네일 미리 준비 (실패해도 요청 시 다시 시도)
+            if let Ok(path) = result {
+                if request.mode != DownloadMode::Audio {
+                    let duration = ytdlp
+                        .library()
+                        .get(&cache_key)
+                        .and_then(|entry| entry.duration);
+                    if let Err(e) = thumbnails
+                        .thumbnail(
+                            &request.source.key_id(&request.video_id),
+                            Some(&path),
+                            duration,
+                        )
+                        .await
+                    {
+                        tracing::debug!("Thumbnail for {} not created: {}", request.video_id, e);
+                    }
+                }
+                thumbnails.remove_orphans(ytdlp.library(), started_at).await;
+            }
         });
 
         rx
     }
 }
+
+/// 트랙 변경 이벤트를 받아 이전 요청으로 알고 있는 영상을 미리 다운로드
+/// 현재 트랙은 바로, 다음 트랙은 백그라운드(낮은 우선순위)로 받음
+async fn run_prefetch(
+    coordinator: Arc<DownloadCoordinator>,
+    mut track_events: mpsc::UnboundedReceiver<TrackEvent>,
+) {
+    while let Some(event) = track_events.recv().await {
+        let enabled = coordinator
+            .ytdlp
+            .read_config()
+            .await
+            .map(|config| config.videoPrefetch)
+            .unwrap_or(true);
+        if !enabled {
+            continue;
+        }
+
+        let (track, background) = match event {
+            TrackEvent::Changed(track) => (
+                RequestedTrack {
+                    title: track.title,
+                    artist: Some(track.artist),
+                    album: Some(track.album),
+                    duration: Some(track.duration),
+                    uri: track.uri,
+                },
+                false,
+            ),
+            TrackEvent::Next(next) => (
+                RequestedTrack {
+                    title: next.title,
+                    artist: Some(next.artist),
+                    album: None,
+                    duration: None,
+                    uri: next.uri,
+                },
+                true,
+            ),
+        };
+        let artist = track.artist.as_deref().filter(|a| !a.is_empty());
+
+        let Some(mapping) = coordinator
+            .mappings
+            .lookup(&track.title, artist, track.uri.as_deref())
+        else {
+            continue;
+        };
+
+        let request = DownloadRequest {
+            source: mapping.video_source,
+            video_id: mapping.video_id,
+            mode: DownloadMode::Video,
+            clip: None,
+            track: Some(track),
+            live: false,
+        };
+        if coordinator
+            .ytdlp
+            .cached_file(&request.cache_key())
+            .is_some()
+        {
+            continue;
+        }
+
+        tracing::info!(
+            "Prefetching video {} ({})",
+            request.video_id,
+            if background {
+                "next track"
+            } else {
+                "current track"
+            }
+        );
+        if background {
+            coordinator.enqueue_background(request);
+        } else {
+            let _ = coordinator.start_or_subscribe(request).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AppConfig;
+    use axum::body::Body;
+    use tower::ServiceExt;
+
+    /// 비디오 폴더 밖에 비밀 파일이 있는 임시 데이터 폴더와 /video/files 라우터
+    fn test_router(root: &std::path::Path) -> Router {
+        let videos_dir = root.join("videos");
+        std::fs::create_dir_all(videos_dir.join("sub")).unwrap();
+        std::fs::write(root.join("secret.txt"), "secret").unwrap();
+        std::fs::write(videos_dir.join(".hidden"), "hidden").unwrap();
+        std::fs::write(videos_dir.join("sub").join("abc123def45.mp4"), "nested").unwrap();
+        std::fs::write(videos_dir.join("abc123def45.mp4"), "video").unwrap();
+
+        let ytdlp = YtDlpManager::with_data_dir(
+            videos_dir.clone(),
+            root.to_path_buf(),
+            &AppConfig::default(),
+        );
+        let coordinator = DownloadCoordinator::new(ytdlp, TrackMappingStore::new(root));
+        files_router(Arc::new(coordinator), videos_dir)
+    }
+
+    async fn status(router: &Router, uri: &str) -> StatusCode {
+        let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        router.clone().oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn serves_files_in_videos_dir() {
+        let root = tempfile::tempdir().unwrap();
+        let router = test_router(root.path());
+        assert_eq!(
+            status(&router, "/video/files/abc123def45.mp4").await,
+            StatusCode::OK
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_paths_outside_videos_dir() {
+        let root = tempfile::tempdir().unwrap();
+        let router = test_router(root.path());
+
+        for uri in [
+            "/video/files/../secret.txt",
+            "/video/files/..%2fsecret.txt",
+            "/video/files/..%2Fsecret.txt",
+            "/video/files/%2e%2e/secret.txt",
+            "/video/files/%2e%2e%2fsecret.txt",
+            "/video/files/%2E%2E%2Fsecret.txt",
+            "/video/files/..%5csecret.txt",
+            "/video/files/..\\secret.txt",
+            "/video/files/%2fetc%2fpasswd",
+            "/video/files//etc/passwd",
+            "/video/files/sub/abc123def45.mp4",
+            "/video/files/sub%2fabc123def45.mp4",
+            "/video/files/.hidden",
+            "/video/files/%2ehidden",
+            "/video/files/",
+        ] {
+            let status = status(&router, uri).await;
+            assert!(
+                matches!(status, StatusCode::BAD_REQUEST | StatusCode::NOT_FOUND),
+                "{} returned {}",
+                uri,
+                status
+            );
+        }
+    }
+}
@@ -0,0 +1,356 @@
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+
+/// 영상 출처 (yt-dlp extractor)
+///
+/// YouTube 외의 출처는 캐시 키 앞에 `{source}~`를 붙여 id가 겹치지 않게 함
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VideoSource {
+    #[default]
+    Youtube,
+    Bilibili,
+    Niconico,
+    Vimeo,
+    /// yt-dlp가 지원하는 임의의 영상 URL (id 자리에 URL 전체)
+    Url,
+    /// 로컬 파일에서 가져온 영상 (다운로드 불가)
+    Local,
+}
+
+/// 캐시 키에서 출처와 id를 나누는 구분자 (YouTube id에는 쓰이지 않는 문자)
+const KEY_SEPARATOR: char = '~';
+
+const ALL_SOURCES: &[VideoSource] = &[
+    VideoSource::Youtube,
+    VideoSource::Bilibili,
+    VideoSource::Niconico,
+    VideoSource::Vimeo,
+    VideoSource::Url,
+    VideoSource::Local,
+];
+
+impl VideoSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VideoSource::Youtube => "youtube",
+            VideoSource::Bilibili => "bilibili",
+            VideoSource::Niconico => "niconico",
+            VideoSource::Vimeo => "vimeo",
+            VideoSource::Url => "url",
+            VideoSource::Local => "local",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        ALL_SOURCES.iter().copied().find(|s| s.as_str() == name)
+    }
+
+    /// 출처별 id 형식 확인
+    ///
+    /// - youtube: 11자 (`A-Z a-z 0-9 - _`)
+    /// - bilibili: `BV` + 10자 또는 `av` + 숫자
+    /// - niconico: `sm`/`nm`/`so` + 숫자
+    /// - vimeo: 숫자
+    /// - url: http(s) URL (loopback/사설망 주소 불가)
+    /// - local: 64자 이하 (`A-Z a-z 0-9 - _`, `-`로 시작 불가)
+    pub fn validate_id(&self, id: &str) -> Result<(), String> {
+        let digits =
+            |s: &str| !s.is_empty() && s.len() <= 20 && s.bytes().all(|b| b.is_ascii_digit());
+        let id_chars = |s: &str| {
+            s.bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+        };
+
+        let valid = match self {
+            VideoSource::Youtube => id.len() == 11 && id_chars(id),
+            VideoSource::Bilibili => {
+                (id.len() == 12
+                    && id.starts_with("BV")
+                    && id.bytes().all(|b| b.is_ascii_alphanumeric()))
+                    || id.strip_prefix("av").is_some_and(digits)
+            }
+            VideoSource::Niconico => ["sm", "nm", "so"]
+                .iter()
+                .any(|prefix| id.strip_prefix(prefix).is_some_and(digits)),
+            VideoSource::Vimeo => digits(id),
+            VideoSource::Url => {
+                let url = Url::parse(id)
+                    .ok()
+                    .filter(|url| matches!(url.scheme(), "http" | "https"))
+                    .ok_or_else(|| "Invalid video URL".to_string())?;
+                let host = url
+                    .host_str()
+                    .ok_or_else(|| "Invalid video URL".to_string())?;
+                let is_local = match host_ip(host) {
+                    Some(ip) => !is_public_ip(ip),
+                    None => is_local_host_name(host),
+                };
+                return if is_local {
+                    Err(LOCAL_URL_ERROR.to_string())
+                } else {
+                    Ok(())
+                };
+            }
+            VideoSource::Local => {
+                !id.is_empty() && id.len() <= 64 && !id.starts_with('-') && id_chars(id)
+            }
+        };
+
+        if valid {
+            Ok(())
+        } else {
+            Err(format!("Invalid {} video ID", self.as_str()))
+        }
+    }
+
+    /// URL 출처의 호스트가 공개 주소로만 해석되는지 확인 (yt-dlp 실행 직전에 호출)
+    ///
+    /// 웹 페이지에서도 요청할 수 있으므로 도메인이 loopback/사설망을 가리키면 거부
+    pub async fn ensure_public_host(&self, id: &str) -> Result<(), String> {
+        if *self != VideoSource::Url {
+            return Ok(());
+        }
+        self.validate_id(id)?;
+        let url = Url::parse(id).map_err(|_| "Invalid video URL".to_string())?;
+        let (Some(host), Some(port)) = (url.host_str(), url.port_or_known_default()) else {
+            return Ok(());
+        };
+        if host_ip(host).is_some() {
+            return Ok(());
+        }
+        let addrs = tokio::net::lookup_host((host, port))
+            .await
+            .map_err(|e| format!("Failed to resolve {}: {}", host, e))?;
+        for addr in addrs {
+            if !is_public_ip(addr.ip()) {
+                return Err(LOCAL_URL_ERROR.to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// yt-dlp에 넘길 URL (로컬 영상은 None)
+    pub fn download_url(&self, id: &str) -> Option<String> {
+        match self {
+            VideoSource::Youtube => Some(format!("https://www.youtube.com/watch?v={}", id)),
+            VideoSource::Bilibili => Some(format!("https://www.bilibili.com/video/{}", id)),
+            VideoSource::Niconico => Some(format!("https://www.nicovideo.jp/watch/{}", id)),
+            VideoSource::Vimeo => Some(format!("https://vimeo.com/{}", id)),
+            VideoSource::Url => Some(id.to_string()),
+            VideoSource::Local => None,
+        }
+    }
+
+    /// 캐시 키/파일 이름에 쓰이는 id (YouTube는 기존 파일과 호환되도록 그대로)
+    ///
+    /// URL은 파일 이름으로 쓸 수 없으므로 고정 해시로 대체
+    pub fn key_id(&self, id: &str) -> String {
+        match self {
+            VideoSource::Youtube => id.to_string(),
+            VideoSource::Url => format!("url{}{}", KEY_SEPARATOR, stable_hash(id)),
+            _ => format!("{}{}{}", self.as_str(), KEY_SEPARATOR, id),
+        }
+    }
+
+    /// 캐시 키의 id 부분에서 출처와 id 복원 (URL 출처는 해시만 복원됨)
+    pub fn from_key_id(key_id: &str) -> (Self, String) {
+        key_id
+            .split_once(KEY_SEPARATOR)
+            .and_then(|(name, id)| Some((Self::from_name(name)?, id.to_string())))
+            .unwrap_or((VideoSource::Youtube, key_id.to_string()))
+    }
+}
+
+const LOCAL_URL_ERROR: &str = "Video URLs pointing to local or private addresses are not allowed";
+
+/// URL 호스트가 IP 주소면 반환 (IPv6는 `[...]` 형태)
+fn host_ip(host: &str) -> Option<IpAddr> {
+    host.trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse()
+        .ok()
+}
+
+fn is_local_host_name(host: &str) -> bool {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    host == "localhost" || host.ends_with(".localhost")
+}
+
+/// 외부에서 접근 가능한 주소인지 (loopback, 사설망, link-local, unique-local, 미지정 주소 제외)
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || a == 0
+                // CGNAT (100.64.0.0/10)
+                || (a == 100 && (64..128).contains(&b)))
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(v4) => is_public_ip(IpAddr::V4(v4)),
+            None => {
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_unique_local()
+                    || ip.is_unicast_link_local())
+            }
+        },
+    }
+}
+
+/// 실행/플랫폼과 관계없이 항상 같은 값을 내는 FNV-1a 64비트 해시 (16자리 hex)
+pub fn stable_hash(text: &str) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in text.as_bytes() {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_youtube_ids() {
+        let source = VideoSource::Youtube;
+        assert!(source.validate_id("dQw4w9WgXcQ").is_ok());
+        assert!(source.validate_id("-bc_123def4").is_ok());
+        for id in [
+            "",
+            "short",
+            "dQw4w9WgXcQx",
+            "../../etc/p",
+            "dQw4w9WgX.Q",
+            "dQw4w9WgX/Q",
+        ] {
+            assert!(source.validate_id(id).is_err(), "{:?}", id);
+        }
+    }
+
+    #[test]
+    fn validate_bilibili_ids() {
+        let source = VideoSource::Bilibili;
+        assert!(source.validate_id("BV1xx411c7mD").is_ok());
+        assert!(source.validate_id("av170001").is_ok());
+        for id in [
+            "BV1xx411c7m",
+            "BV1xx411c7m/",
+            "av",
+            "av12a",
+            "170001",
+            "../BV1xx411c",
+        ] {
+            assert!(source.validate_id(id).is_err(), "{:?}", id);
+        }
+    }
+
+    #[test]
+    fn validate_niconico_ids() {
+        let source = VideoSource::Niconico;
+        for id in ["sm9", "nm2829323", "so12345"] {
+            assert!(source.validate_id(id).is_ok(), "{:?}", id);
+        }
+        for id in ["sm", "9", "xx9", "sm9.mp4", "sm../9"] {
+            assert!(source.validate_id(id).is_err(), "{:?}", id);
+        }
+    }
+
+    #[test]
+    fn validate_vimeo_ids() {
+        let source = VideoSource::Vimeo;
+        assert!(source.validate_id("76979871").is_ok());
+        for id in ["", "7697a", "-76979871", &"1".repeat(21)] {
+            assert!(source.validate_id(id).is_err(), "{:?}", id);
+        }
+    }
+
+    #[test]
+    fn validate_url_ids() {
+        let source = VideoSource::Url;
+        assert!(source.validate_id("https://example.com/watch?v=1").is_ok());
+        assert!(source.validate_id("http://example.com/video").is_ok());
+        for id in [
+            "",
+            "example.com/video",
+            "file:///etc/passwd",
+            "ftp://example.com/a",
+            "http://127.0.0.1/x",
+            "http://2130706433/x",
+            "http://localhost:8080/",
+            "http://a.localhost/",
+            "http://[::1]/",
+            "http://[::ffff:127.0.0.1]/",
+            "http://0.0.0.0/",
+            "http://10.0.0.1/",
+            "http://192.168.1.1/video",
+            "http://169.254.169.254/latest/meta-data/",
+            "http://[fd00::1]/",
+            "http://[fe80::1]/",
+        ] {
+            assert!(source.validate_id(id).is_err(), "{:?}", id);
+        }
+    }
+
+    #[test]
+    fn validate_local_ids() {
+        let source = VideoSource::Local;
+        assert!(source.validate_id("0123456789abcdef").is_ok());
+        assert!(source.validate_id(&"a".repeat(64)).is_ok());
+        for id in [
+            "",
+            "-abc",
+            "a.b",
+            "../secret",
+            "a/b",
+            "a\\b",
+            &"a".repeat(65),
+        ] {
+            assert!(source.validate_id(id).is_err(), "{:?}", id);
+        }
+    }
+
+    #[test]
+    fn key_id_round_trip() {
+        for source in ALL_SOURCES.iter().filter(|s| **s != VideoSource::Url) {
+            let key_id = source.key_id("sm9");
+            assert_eq!(
+                VideoSource::from_key_id(&key_id),
+                (*source, "sm9".to_string())
+            );
+        }
+        assert_eq!(VideoSource::Youtube.key_id("dQw4w9WgXcQ"), "dQw4w9WgXcQ");
+        assert_eq!(VideoSource::Niconico.key_id("sm9"), "niconico~sm9");
+    }
+
+    #[test]
+    fn url_key_id_is_hashed() {
+        let url = "https://example.com/a/../../video?x=1";
+        let key_id = VideoSource::Url.key_id(url);
+        assert_eq!(key_id, format!("url~{}", stable_hash(url)));
+        assert!(!key_id.contains('/'));
+        assert_eq!(
+            VideoSource::from_key_id(&key_id),
+            (VideoSource::Url, stable_hash(url))
+        );
+    }
+
+    #[test]
+    fn unknown_key_prefix_is_youtube() {
+        assert_eq!(
+            VideoSource::from_key_id("dQw4w9WgXcQ"),
+            (VideoSource::Youtube, "dQw4w9WgXcQ".to_string())
+        );
+        assert_eq!(
+            VideoSource::from_key_id("unknown~abc"),
+            (VideoSource::Youtube, "unknown~abc".to_string())
+        );
+    }
+}
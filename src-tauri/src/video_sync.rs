@@ -0,0 +1,107 @@
+use serde::Serialize;
+
+use crate::track_mappings::TrackMapping;
+use crate::ytdlp::ClipRange;
+
+/// 배경 영상을 트랙 재생 위치에 맞추기 위한 힌트
+///
+/// 플레이어는 `video_time = (ProgressData.position + offset_ms) * playback_rate`로 맞춤
+#[derive(Clone, Debug, Serialize)]
+pub struct SyncHint {
+    /// 트랙 0ms에 해당하는 영상 위치 (ms, 음수면 영상이 트랙보다 늦게 시작)
+    pub offset_ms: i64,
+    /// 영상 재생 속도 배율
+    pub playback_rate: f64,
+    pub sync_source: SyncSource,
+}
+
+/// 힌트를 어떻게 얻었는지
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncSource {
+    /// 매핑에 저장된 오프셋
+    Mapping,
+    /// 영상 길이와 트랙 길이를 비교해 추정
+    Duration,
+    /// 정보가 없어 기본값 사용
+    None,
+}
+
+/// 길이 차이가 이 이하면 이미 맞는 것으로 봄
+const ALIGNED_TOLERANCE_MS: i64 = 1_000;
+/// 영상이 이보다 더 길면 인트로가 아닌 다른 편집본으로 보고 추정하지 않음
+const MAX_INTRO_MS: i64 = 60_000;
+/// 영상이 짧을 때 속도 보정을 허용하는 최소 배율
+const MIN_PLAYBACK_RATE: f64 = 0.9;
+
+impl SyncHint {
+    fn none() -> Self {
+        Self {
+            offset_ms: 0,
+            playback_rate: 1.0,
+            sync_source: SyncSource::None,
+        }
+    }
+
+    /// 매핑에 지정된 오프셋 우선 (0도 "이미 맞음"으로 사용), 없으면 길이 비교로 추정
+    ///
+    /// 구간 다운로드라면 파일이 구간 시작부터 시작하므로 그만큼 오프셋에서 뺌
+    pub fn resolve(
+        mapping: Option<&TrackMapping>,
+        video_secs: Option<f64>,
+        track_ms: Option<u64>,
+        clip: Option<&ClipRange>,
+    ) -> Self {
+        match mapping.and_then(|m| m.offset_ms) {
+            Some(offset_ms) => Self {
+                offset_ms: offset_ms - clip.map_or(0, |c| c.start_ms as i64),
+                playback_rate: 1.0,
+                sync_source: SyncSource::Mapping,
+            },
+            None => {
+                let track_ms = track_ms.or_else(|| mapping.and_then(|m| m.duration));
+                match video_secs.zip(track_ms) {
+                    // 구간 다운로드는 길이 비교가 의미 없음
+                    Some((video_secs, track_ms)) if clip.is_none() => {
+                        Self::estimate((video_secs * 1000.0) as i64, track_ms as i64)
+                    }
+                    _ => Self::none(),
+                }
+            }
+        }
+    }
+
+    /// 영상이 조금 길면 앞부분이 인트로라고 보고, 조금 짧으면 속도를 늦춤
+    fn estimate(video_ms: i64, track_ms: i64) -> Self {
+        if track_ms <= 0 || video_ms <= 0 {
+            return Self::none();
+        }
+
+        let diff = video_ms - track_ms;
+        if diff.abs() <= ALIGNED_TOLERANCE_MS {
+            return Self {
+                offset_ms: 0,
+                playback_rate: 1.0,
+                sync_source: SyncSource::Duration,
+            };
+        }
+        if diff > 0 && diff <= MAX_INTRO_MS {
+            return Self {
+                offset_ms: diff,
+                playback_rate: 1.0,
+                sync_source: SyncSource::Duration,
+            };
+        }
+
+        let rate = video_ms as f64 / track_ms as f64;
+        if diff < 0 && rate >= MIN_PLAYBACK_RATE {
+            return Self {
+                offset_ms: 0,
+                playback_rate: rate,
+                sync_source: SyncSource::Duration,
+            };
+        }
+
+        Self::none()
+    }
+}
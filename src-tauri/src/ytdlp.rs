@@ -1,13 +1,26 @@
+use crate::binaries::{
+    self, version_at_least, version_parts, Binaries, Binary, BinaryLocation, BinaryStatus,
+    JS_RUNTIMES_YTDLP_VERSION,
+};
+use crate::binary_download::{BinaryDownloadState, BinaryDownloads};
+use crate::checksum::{self, ReleaseAsset, Verification};
 use crate::config::AppConfig;
-use regex::Regex;
+use crate::cookies::{self, CookieSource, CookieStateStore};
+use crate::download_error::{DownloadError, DownloadErrorCode};
+use crate::network;
+use crate::video_info::VideoInfo;
+use crate::video_library::{entry_from_info_json, RequestedTrack, VideoLibrary};
+use crate::video_source::VideoSource;
 use reqwest::Client;
 
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
-use std::time::SystemTime;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, Mutex};
 
 #[cfg(windows)]
 const CREATE_NO_WINDOW: u32 = 0x0800_0000;
@@ -18,9 +31,116 @@ pub struct DownloadProgress {
     pub video_id: String,
     pub status: DownloadStatus,
     pub percent: Option<f32>,
+    /// 표시용 속도 (`1.5MiB/s`)
     pub speed: Option<String>,
+    /// 표시용 남은 시간 (`01:23`)
     pub eta: Option<String>,
     pub message: Option<String>,
+    /// 다운로드 중일 때 바이트/조각 단위 상태
+    #[serde(flatten)]
+    pub transfer: Option<TransferProgress>,
+    /// 실패 원인 (status가 error일 때)
+    pub error_code: Option<DownloadErrorCode>,
+}
+
+/// `--progress-template` 출력 줄 앞에 붙이는 표시 (다른 출력과 구분)
+const PROGRESS_PREFIX: &str = "ivlyrics-progress:";
+/// 진행 상황을 JSON 한 줄로 출력하는 템플릿
+const PROGRESS_TEMPLATE: &str = "download:ivlyrics-progress:%(progress.{status,downloaded_bytes,total_bytes,total_bytes_estimate,fragment_index,fragment_count,speed,eta})j";
+
+/// yt-dlp가 출력하는 진행 상황 (필드는 상황에 따라 없거나 null, 정수/실수 혼용)
+#[derive(serde::Deserialize)]
+struct TemplateProgress {
+    status: Option<String>,
+    downloaded_bytes: Option<f64>,
+    total_bytes: Option<f64>,
+    total_bytes_estimate: Option<f64>,
+    fragment_index: Option<f64>,
+    fragment_count: Option<f64>,
+    speed: Option<f64>,
+    eta: Option<f64>,
+}
+
+/// 다운로드 중인 파일의 전송 상태
+#[derive(Clone, Debug, Default, serde::Serialize)]
+pub struct TransferProgress {
+    pub downloaded_bytes: Option<u64>,
+    /// 전체 크기 (모르면 yt-dlp 추정값)
+    pub total_bytes: Option<u64>,
+    /// total_bytes가 추정값인지
+    pub total_bytes_estimated: bool,
+    /// 조각(HLS/DASH) 다운로드일 때 현재 조각 번호와 전체 조각 수
+    pub fragment_index: Option<u64>,
+    pub fragment_count: Option<u64>,
+    /// 초당 바이트
+    pub speed_bytes: Option<f64>,
+    pub eta_seconds: Option<u64>,
+}
+
+impl TransferProgress {
+    /// 진행 상황 템플릿 줄 파싱 (다운로드 중 상태가 아니면 None)
+    fn parse_line(line: &str) -> Option<Self> {
+        let json = line.trim().strip_prefix(PROGRESS_PREFIX)?;
+        let raw: TemplateProgress = serde_json::from_str(json).ok()?;
+        if raw.status.as_deref() != Some("downloading") {
+            return None;
+        }
+
+        let count = |value: Option<f64>| {
+            value
+                .filter(|v| v.is_finite() && *v >= 0.0)
+                .map(|v| v as u64)
+        };
+        let total_bytes = count(raw.total_bytes).filter(|total| *total > 0);
+        Some(Self {
+            downloaded_bytes: count(raw.downloaded_bytes),
+            total_bytes_estimated: total_bytes.is_none()
+                && count(raw.total_bytes_estimate).is_some_and(|total| total > 0),
+            total_bytes: total_bytes
+                .or_else(|| count(raw.total_bytes_estimate).filter(|total| *total > 0)),
+            fragment_index: count(raw.fragment_index),
+            fragment_count: count(raw.fragment_count).filter(|total| *total > 0),
+            speed_bytes: raw.speed.filter(|v| v.is_finite() && *v >= 0.0),
+            eta_seconds: count(raw.eta),
+        })
+    }
+
+    /// 바이트 기준 진행률, 크기를 모르면 조각 기준
+    fn percent(&self) -> Option<f32> {
+        let ratio = match (self.downloaded_bytes, self.total_bytes) {
+            (Some(done), Some(total)) => done as f64 / total as f64,
+            _ => self.fragment_index? as f64 / self.fragment_count? as f64,
+        };
+        Some((ratio * 100.0).clamp(0.0, 100.0) as f32)
+    }
+
+    fn speed_text(&self) -> Option<String> {
+        self.speed_bytes
+            .map(|speed| format!("{}/s", format_bytes(speed)))
+    }
+
+    fn eta_text(&self) -> Option<String> {
+        self.eta_seconds.map(|eta| {
+            let (hours, minutes, seconds) = (eta / 3600, eta / 60 % 60, eta % 60);
+            if hours > 0 {
+                format!("{}:{:02}:{:02}", hours, minutes, seconds)
+            } else {
+                format!("{:02}:{:02}", minutes, seconds)
+            }
+        })
+    }
+}
+
+/// 사람이 읽기 쉬운 크기 (`12.3MiB`)
+fn format_bytes(bytes: f64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.2}{}", value, UNITS[unit])
 }
 
 #[derive(Clone, Debug, serde::Serialize, PartialEq)]
@@ -34,36 +154,596 @@ pub enum DownloadStatus {
     AlreadyExists,
 }
 
+/// 다운로드 모드
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DownloadMode {
+    /// 무음 영상 (배경 재생용)
+    #[default]
+    Video,
+    /// 오디오만 (opus/m4a)
+    Audio,
+    /// 영상 + 오디오 병합
+    Muxed,
+}
+
+impl DownloadMode {
+    /// yt-dlp 포맷 선택자
+    fn format_selector(&self) -> &'static str {
+        match self {
+            DownloadMode::Video => {
+                "bestvideo[height<=1080][ext=webm]/bestvideo[height<=1080]/bestvideo[ext=webm]/bestvideo"
+            }
+            DownloadMode::Audio => "bestaudio[ext=webm]/bestaudio[ext=m4a]/bestaudio",
+            DownloadMode::Muxed => "bestvideo[height<=1080]+bestaudio/best[height<=1080]/best",
+        }
+    }
+
+    /// 받으면서 재생할 수 있는 단일 파일 포맷 (병합 없이 앞에서부터 쓰이는 webm 우선)
+    fn live_format_selector(&self) -> &'static str {
+        match self {
+            DownloadMode::Video => {
+                "bestvideo[height<=1080][ext=webm]/bestvideo[ext=webm]/bestvideo[height<=1080][ext=mp4]"
+            }
+            DownloadMode::Audio => "bestaudio[ext=webm]/bestaudio[ext=m4a]",
+            DownloadMode::Muxed => "best[height<=1080][ext=webm]/best[height<=1080][ext=mp4]",
+        }
+    }
+
+    /// 캐시 키 접미사 (video 모드는 기존 파일명과 호환되도록 접미사 없음)
+    fn key_suffix(&self) -> Option<&'static str> {
+        match self {
+            DownloadMode::Video => None,
+            DownloadMode::Audio => Some("audio"),
+            DownloadMode::Muxed => Some("muxed"),
+        }
+    }
+
+    /// 캐시 키 접미사로부터 모드 추정
+    fn from_key_suffix(suffix: Option<&str>) -> Self {
+        match suffix {
+            Some("audio") => DownloadMode::Audio,
+            Some("muxed") => DownloadMode::Muxed,
+            _ => DownloadMode::Video,
+        }
+    }
+}
+
+/// 잘라서 받을 구간 (ms)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ClipRange {
+    pub start_ms: u64,
+    /// None이면 영상 끝까지
+    pub end_ms: Option<u64>,
+}
+
+impl ClipRange {
+    /// start/end 쿼리 값 파싱 (`90`, `90.5`, `1:30`, `1:02:03.5` 형식)
+    /// 둘 다 없으면 None
+    pub fn parse(start: Option<&str>, end: Option<&str>) -> Result<Option<Self>, String> {
+        let start = start.map(str::trim).filter(|s| !s.is_empty());
+        let end = end.map(str::trim).filter(|s| !s.is_empty());
+        if start.is_none() && end.is_none() {
+            return Ok(None);
+        }
+
+        let start_ms = match start {
+            Some(value) => {
+                parse_timestamp_ms(value).ok_or_else(|| format!("Invalid start: {}", value))?
+            }
+            None => 0,
+        };
+        let end_ms = match end {
+            Some(value) => {
+                Some(parse_timestamp_ms(value).ok_or_else(|| format!("Invalid end: {}", value))?)
+            }
+            None => None,
+        };
+
+        if let Some(end_ms) = end_ms {
+            if end_ms <= start_ms {
+                return Err("end must be greater than start".to_string());
+            }
+        }
+
+        Ok(Some(Self { start_ms, end_ms }))
+    }
+
+    /// 캐시 키 조각 (예: `clip-30000-210000`, `clip-30000-end`)
+    fn key_part(&self) -> String {
+        match self.end_ms {
+            Some(end_ms) => format!("clip-{}-{}", self.start_ms, end_ms),
+            None => format!("clip-{}-end", self.start_ms),
+        }
+    }
+
+    fn from_key_part(part: &str) -> Option<Self> {
+        let (start, end) = part.strip_prefix("clip-")?.split_once('-')?;
+        Some(Self {
+            start_ms: start.parse().ok()?,
+            end_ms: if end == "end" {
+                None
+            } else {
+                Some(end.parse().ok()?)
+            },
+        })
+    }
+
+    /// yt-dlp --download-sections 값 (초 단위)
+    fn download_section(&self) -> String {
+        let seconds = |ms: u64| format!("{}.{:03}", ms / 1000, ms % 1000);
+        match self.end_ms {
+            Some(end_ms) => format!("*{}-{}", seconds(self.start_ms), seconds(end_ms)),
+            None => format!("*{}-inf", seconds(self.start_ms)),
+        }
+    }
+}
+
+/// `SS`, `SS.mmm`, `MM:SS`, `HH:MM:SS(.mmm)` 형식을 ms로 변환
+fn parse_timestamp_ms(value: &str) -> Option<u64> {
+    let mut total = 0.0f64;
+    for part in value.split(':') {
+        let number: f64 = part.parse().ok()?;
+        if !number.is_finite() || number < 0.0 {
+            return None;
+        }
+        total = total * 60.0 + number;
+    }
+    Some((total * 1000.0).round() as u64)
+}
+
+/// 다운로드 요청 (비디오 id와 옵션)
+#[derive(Clone, Debug)]
+pub struct DownloadRequest {
+    pub source: VideoSource,
+    /// 출처별 id (url 출처는 URL 전체)
+    pub video_id: String,
+    pub mode: DownloadMode,
+    /// 지정하면 해당 구간만 다운로드
+    pub clip: Option<ClipRange>,
+    pub track: Option<RequestedTrack>,
+    /// 다운로드 중에도 재생할 수 있도록 스트리밍 가능한 포맷을 `{key}.live.{ext}`에 바로 기록
+    pub live: bool,
+}
+
+/// 스트리밍 다운로드 중인 파일 이름 표시 (`{key}.live.{ext}`)
+const LIVE_FILE_MARKER: &str = "live";
+
+/// 아직 다운로드 중인 파일인지 (.part/.ytdl 임시 파일, 스트리밍 중인 .live 파일)
+pub fn is_incomplete_file(path: &Path) -> bool {
+    let extension = path.extension().and_then(|e| e.to_str());
+    let stem_extension = path
+        .file_stem()
+        .and_then(|stem| Path::new(stem).extension())
+        .and_then(|e| e.to_str());
+    matches!(extension, Some("part") | Some("ytdl")) || stem_extension == Some(LIVE_FILE_MARKER)
+}
+
+/// yt-dlp 버전(`2024.08.06`, 나이틀리는 `2024.08.06.232617`)을 숫자 단위로 비교
+fn is_newer_version(installed: Option<&str>, latest: Option<&str>) -> bool {
+    match (installed, latest) {
+        (Some(installed), Some(latest)) => version_parts(latest) > version_parts(installed),
+        (None, Some(_)) => true,
+        _ => false,
+    }
+}
+
+/// `staged`를 `target`으로 옮긴 뒤 실행해 보고, 버전과 기존 파일을 옮겨 둔 `backup` 경로 반환
+///
+/// 옮기기나 실행에 실패하면 기존 파일을 제자리로 돌려놓음
+/// (백업은 호출한 쪽에서 다른 파일까지 모두 성공한 뒤 삭제)
+async fn install_with_backup(
+    binary: Binary,
+    staged: &Path,
+    target: &Path,
+    backup: &Path,
+) -> Result<(String, Option<PathBuf>), String> {
+    let had_previous = target.exists();
+    if had_previous {
+        let _ = tokio::fs::remove_file(backup).await;
+        tokio::fs::rename(target, backup)
+            .await
+            .map_err(|e| format!("Failed to back up {:?}: {}", target, e))?;
+    }
+
+    let installed = match tokio::fs::rename(staged, target).await {
+        Ok(()) => binaries::probe_version(binary, target).await,
+        Err(e) => Err(e.to_string()),
+    };
+    match installed {
+        Ok(version) => Ok((version, had_previous.then(|| backup.to_path_buf()))),
+        Err(e) => {
+            if had_previous {
+                let _ = tokio::fs::remove_file(target).await;
+                let _ = tokio::fs::rename(backup, target).await;
+            }
+            Err(e)
+        }
+    }
+}
+
+/// 번들(zip)에서 실행 파일을 `{이름}.new`로 풀면서 SHA-256 계산하고 SHA2-256SUMS 읽기
+/// (폴더 구조는 무시하고 파일 이름으로 찾음)
+///
+/// yt-dlp는 릴리스 파일 이름(yt-dlp_macos 등)으로 들어 있어도 인식
+/// ffmpeg가 든 번들은 수백 MB라 메모리에 올리지 않고 zip 파일에서 바로 읽음
+/// 실패하면 풀어 둔 파일을 지움
+fn extract_bundle(
+    archive: &Path,
+    data_dir: &Path,
+) -> Result<(Vec<BundleFile>, Option<String>), String> {
+    let mut files: Vec<BundleFile> = Vec::new();
+    let result = extract_bundle_files(archive, data_dir, &mut files);
+    if result.is_err() {
+        for file in &files {
+            let _ = std::fs::remove_file(&file.staged);
+        }
+    }
+    result.map(|sums| (files, sums))
+}
+
+fn extract_bundle_files(
+    archive: &Path,
+    data_dir: &Path,
+    files: &mut Vec<BundleFile>,
+) -> Result<Option<String>, String> {
+    use sha2::{Digest, Sha256};
+    use std::io::{Read, Write};
+
+    let archive_file =
+        std::fs::File::open(archive).map_err(|e| format!("Failed to read {:?}: {}", archive, e))?;
+    let mut archive = zip::ZipArchive::new(std::io::BufReader::new(archive_file))
+        .map_err(|e| format!("Invalid bundle archive: {}", e))?;
+    let mut sums = None;
+
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index).map_err(|e| e.to_string())?;
+        if !entry.is_file() {
+            continue;
+        }
+        let Some(name) = Path::new(entry.name())
+            .file_name()
+            .and_then(|n| n.to_str())
+            .map(|n| n.to_string())
+        else {
+            continue;
+        };
+
+        let binary =
+            if name == Binary::YtDlp.file_name() || name == YtDlpManager::get_ytdlp_binary_name() {
+                Binary::YtDlp
+            } else if name == Binary::Ffmpeg.file_name() {
+                Binary::Ffmpeg
+            } else if name == Binary::Deno.file_name() {
+                Binary::Deno
+            } else if name == checksum::SUMS_FILE_NAME {
+                let mut text = String::new();
+                entry
+                    .read_to_string(&mut text)
+                    .map_err(|e| format!("Failed to read {}: {}", name, e))?;
+                sums = Some(text);
+                continue;
+            } else {
+                continue;
+            };
+        if files.iter().any(|file| file.binary == binary) {
+            return Err(format!("Bundle contains more than one {:?}", binary));
+        }
+
+        let staged = data_dir.join(format!("{}.new", binary.file_name()));
+        let mut output = std::fs::File::create(&staged)
+            .map_err(|e| format!("Failed to create {:?}: {}", staged, e))?;
+        files.push(BundleFile {
+            binary,
+            name: name.clone(),
+            staged: staged.clone(),
+            sha256: String::new(),
+        });
+
+        let mut hasher = Sha256::new();
+        let mut buf = vec![0u8; 64 * 1024];
+        loop {
+            let n = entry
+                .read(&mut buf)
+                .map_err(|e| format!("Failed to read {}: {}", name, e))?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buf[..n]);
+            output
+                .write_all(&buf[..n])
+                .map_err(|e| format!("Failed to write {:?}: {}", staged, e))?;
+        }
+        output
+            .sync_all()
+            .map_err(|e| format!("Failed to write {:?}: {}", staged, e))?;
+        if let Some(file) = files.last_mut() {
+            file.sha256 = format!("{:x}", hasher.finalize());
+        }
+    }
+
+    Ok(sums)
+}
+
+/// URL 경로로 받은 캐시 키/파일 이름이 비디오 폴더 안의 한 파일만 가리키는지
+///
+/// 영문/숫자/`-_~.`만 허용하고 경로 구분자, `..`, `.`로 시작하는 이름은 거부
+/// (YouTube id는 `-`로 시작할 수 있으므로 허용, yt-dlp 인자로는 항상 `--` 뒤에 전달)
+pub fn is_safe_cache_key(key: &str) -> bool {
+    !key.is_empty()
+        && key.len() <= 128
+        && !key.starts_with('.')
+        && !key.contains("..")
+        && key
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'~' | b'.'))
+}
+
+/// 캐시 키의 id 부분 (모드/구간 접미사 제외, 예: `abc123.audio.clip-0-1000` → `abc123`)
+pub fn cache_key_id(key: &str) -> &str {
+    key.split('.').next().unwrap_or_default()
+}
+
+impl DownloadRequest {
+    /// 캐시 파일 이름 (확장자 제외)
+    /// 예) `abc123` (video), `abc123.audio`, `abc123.muxed.clip-30000-210000`, `niconico~sm9`
+    pub fn cache_key(&self) -> String {
+        let mut key = self.source.key_id(&self.video_id);
+        if let Some(suffix) = self.mode.key_suffix() {
+            key.push('.');
+            key.push_str(suffix);
+        }
+        if let Some(clip) = &self.clip {
+            key.push('.');
+            key.push_str(&clip.key_part());
+        }
+        key
+    }
+
+    /// 캐시 키에서 요청 정보 복원 (카탈로그에 없는 기존 파일용)
+    pub fn from_cache_key(key: &str) -> Self {
+        let mut parts = key.split('.');
+        let (source, video_id) = VideoSource::from_key_id(parts.next().unwrap_or_default());
+        let mut mode = DownloadMode::Video;
+        let mut clip = None;
+        for part in parts {
+            if let Some(range) = ClipRange::from_key_part(part) {
+                clip = Some(range);
+            } else {
+                mode = DownloadMode::from_key_suffix(Some(part));
+            }
+        }
+
+        Self {
+            source,
+            video_id,
+            mode,
+            clip,
+            track: None,
+            live: false,
+        }
+    }
+}
+
+/// 설치된 yt-dlp와 최신 릴리스 버전
+#[derive(Clone, Debug, Default, serde::Serialize)]
+pub struct YtDlpVersion {
+    /// `yt-dlp --version` 결과 (설치되지 않았거나 실행 실패면 None)
+    pub installed: Option<String>,
+    /// GitHub 최신 릴리스 태그
+    pub latest: Option<String>,
+    pub update_available: bool,
+    /// 마지막 확인 시각 (unix seconds)
+    pub checked_at: Option<u64>,
+    /// 마지막 확인/업데이트 실패 메시지
+    pub error: Option<String>,
+    /// 마지막으로 받은 실행 파일의 체크섬 확인 결과
+    pub verification: Option<Verification>,
+}
+
+/// GitHub 최신 릴리스 중 현재 플랫폼용 yt-dlp
+struct YtDlpRelease {
+    version: String,
+    asset: ReleaseAsset,
+    /// 체크섬 파일을 찾기 위한 전체 asset 목록
+    assets: Vec<ReleaseAsset>,
+}
+
+/// 앱 시작 후 첫 업데이트 확인까지 대기
+const UPDATE_CHECK_DELAY: Duration = Duration::from_secs(60);
+
+/// 미러를 설정하지 않았을 때 릴리스를 조회하는 주소
+const GITHUB_API_BASE_URL: &str = "https://api.github.com";
+/// releaseDownloadBaseUrl로 바꿔 쓰는 GitHub 다운로드 주소
+const GITHUB_DOWNLOAD_BASE_URL: &str = "https://github.com";
+
+/// 오프라인 번들에서 꺼낸 실행 파일
+struct BundleFile {
+    binary: Binary,
+    /// 번들 안의 파일 이름
+    name: String,
+    /// 앱 폴더에 풀어 둔 위치 (`{이름}.new`)
+    staged: PathBuf,
+    sha256: String,
+}
+
 /// yt-dlp 관리자
 #[derive(Clone)]
 pub struct YtDlpManager {
-    client: Client,
+    /// 네트워크 설정(프록시 등)을 적용한 HTTP 클라이언트, 설정이 바뀌면 교체
+    client: Arc<std::sync::RwLock<Client>>,
     data_dir: PathBuf,
     videos_dir: PathBuf,
+    library: VideoLibrary,
+    /// 마지막 버전 확인 결과
+    version: Arc<std::sync::Mutex<YtDlpVersion>>,
+    /// 설치/업데이트는 한 번에 하나씩
+    install_lock: Arc<Mutex<()>>,
+    /// 설정에 따라 감지한 yt-dlp/ffmpeg/deno
+    binaries: Arc<std::sync::RwLock<Binaries>>,
+    /// yt-dlp/Deno/인스톨러 다운로드 진행 상황
+    binary_downloads: BinaryDownloads,
+    /// 마지막으로 성공한 쿠키 출처
+    cookie_state: CookieStateStore,
+    /// 캐시 정리에서 잠시 제외할 캐시 키 (작업별, 재생목록 다운로드 중 앞서 받은 항목 보호)
+    pruning_exemptions: Arc<std::sync::Mutex<HashMap<String, HashSet<String>>>>,
 }
 
 impl YtDlpManager {
-    pub fn new(videos_dir: PathBuf) -> Self {
+    pub fn new(videos_dir: PathBuf, config: &AppConfig) -> Self {
         // macOS: ~/Library/Application Support, Windows: %LOCALAPPDATA%
         let data_dir = dirs::data_local_dir()
             .unwrap_or_else(|| PathBuf::from("."))
             .join("ivLyrics-helper");
 
+        Self::with_data_dir(videos_dir, data_dir, config)
+    }
+
+    /// 앱 데이터 폴더를 직접 지정 (카탈로그, 실행 파일, 쿠키 상태 위치)
+    pub fn with_data_dir(videos_dir: PathBuf, data_dir: PathBuf, config: &AppConfig) -> Self {
+        let library = VideoLibrary::new(&data_dir);
+        let cookie_state = CookieStateStore::new(&data_dir);
+
         Self {
-            client: Client::new(),
+            client: Arc::new(std::sync::RwLock::new(network::build_client(config))),
             data_dir,
             videos_dir,
+            library,
+            version: Arc::new(std::sync::Mutex::new(YtDlpVersion::default())),
+            install_lock: Arc::new(Mutex::new(())),
+            binaries: Arc::new(std::sync::RwLock::new(Binaries::default())),
+            binary_downloads: BinaryDownloads::new(),
+            cookie_state,
+            pruning_exemptions: Arc::new(std::sync::Mutex::new(HashMap::new())),
         }
     }
 
-    /// yt-dlp 실행 파일 경로 (플랫폼별)
+    /// 사용할 yt-dlp 실행 파일 경로 (감지된 파일이 없으면 앱 폴더의 경로)
     pub fn ytdlp_path(&self) -> PathBuf {
-        if cfg!(target_os = "windows") {
-            self.data_dir.join("yt-dlp.exe")
+        self.binaries
+            .read()
+            .ok()
+            .and_then(|binaries| {
+                binaries
+                    .ytdlp
+                    .as_ref()
+                    .and_then(BinaryStatus::usable_path)
+                    .map(Path::to_path_buf)
+            })
+            .unwrap_or_else(|| self.bundled_ytdlp_path())
+    }
+
+    /// 앱이 받아 관리하는 yt-dlp 경로
+    fn bundled_ytdlp_path(&self) -> PathBuf {
+        self.data_dir.join(Binary::YtDlp.file_name())
+    }
+
+    /// yt-dlp/Deno/인스톨러 다운로드 진행 상황
+    pub fn binary_downloads(&self) -> &BinaryDownloads {
+        &self.binary_downloads
+    }
+
+    /// 마지막으로 성공한 쿠키 출처 기록
+    pub fn cookie_state(&self) -> &CookieStateStore {
+        &self.cookie_state
+    }
+
+    /// 네트워크 설정을 적용한 HTTP 클라이언트
+    pub fn http_client(&self) -> Client {
+        self.client
+            .read()
+            .map(|client| client.clone())
+            .unwrap_or_default()
+    }
+
+    /// 바뀐 네트워크 설정으로 HTTP 클라이언트 교체 (yt-dlp 인자는 실행할 때마다 설정에서 읽음)
+    pub fn apply_network_config(&self, config: &AppConfig) {
+        if let Ok(mut client) = self.client.write() {
+            *client = network::build_client(config);
+        }
+    }
+
+    /// 설정(ytdlpLocation/ffmpegLocation/denoLocation)에 따라 실행 파일을 다시 감지
+    pub async fn detect_binaries(&self) -> Binaries {
+        let config = self.read_config().await.unwrap_or_default();
+        let detected = Binaries {
+            ytdlp: Some(
+                binaries::detect(Binary::YtDlp, &config.ytdlpLocation, &self.data_dir).await,
+            ),
+            ffmpeg: Some(
+                binaries::detect(Binary::Ffmpeg, &config.ffmpegLocation, &self.data_dir).await,
+            ),
+            deno: Some(binaries::detect(Binary::Deno, &config.denoLocation, &self.data_dir).await),
+            detected_at: Some(crate::video_library::now_secs()),
+        };
+        for status in [&detected.ytdlp, &detected.ffmpeg, &detected.deno]
+            .into_iter()
+            .flatten()
+        {
+            match (&status.path, &status.error) {
+                (Some(path), None) => tracing::info!("Using {:?} {:?}", status.binary, path),
+                (_, Some(e)) => tracing::info!("{:?} unavailable: {}", status.binary, e),
+                _ => {}
+            }
+        }
+
+        if let Ok(mut binaries) = self.binaries.write() {
+            *binaries = detected.clone();
+        }
+        detected
+    }
+
+    /// 감지 결과 (아직 감지하지 않았으면 지금 감지)
+    pub async fn binaries(&self) -> Binaries {
+        let current = self
+            .binaries
+            .read()
+            .map(|binaries| binaries.clone())
+            .unwrap_or_default();
+        if current.detected_at.is_some() {
+            current
         } else {
-            // macOS, Linux
-            self.data_dir.join("yt-dlp")
+            self.detect_binaries().await
+        }
+    }
+
+    /// 사용할 수 있는 ffmpeg 경로
+    pub async fn ffmpeg_path(&self) -> Option<PathBuf> {
+        self.binaries()
+            .await
+            .ffmpeg
+            .as_ref()
+            .and_then(BinaryStatus::usable_path)
+            .map(Path::to_path_buf)
+    }
+
+    /// 감지한 ffmpeg/deno를 yt-dlp에 알려주는 인자
+    /// (`--js-runtimes`는 지원하는 yt-dlp 버전에서만 전달)
+    async fn binary_args(&self) -> Vec<String> {
+        let binaries = self.binaries().await;
+        let mut args = Vec::new();
+
+        if let Some(ffmpeg) = binaries.ffmpeg.as_ref().and_then(BinaryStatus::usable_path) {
+            args.push("--ffmpeg-location".to_string());
+            args.push(ffmpeg.to_string_lossy().to_string());
+        }
+
+        let supports_js_runtimes = binaries
+            .ytdlp
+            .as_ref()
+            .and_then(|status| status.version.as_deref())
+            .is_some_and(|version| version_at_least(version, JS_RUNTIMES_YTDLP_VERSION));
+        if let Some(deno) = binaries
+            .deno
+            .as_ref()
+            .and_then(BinaryStatus::usable_path)
+            .filter(|_| supports_js_runtimes)
+        {
+            args.push("--js-runtimes".to_string());
+            args.push(format!("deno:{}", deno.to_string_lossy()));
         }
+
+        args
     }
 
     /// 현재 플랫폼에 맞는 yt-dlp 바이너리 이름 반환
@@ -81,14 +761,43 @@ impl YtDlpManager {
         }
     }
 
+    /// 앱 데이터 디렉토리
+    pub fn data_dir(&self) -> PathBuf {
+        self.data_dir.clone()
+    }
+
     /// 비디오 저장 디렉토리
     pub fn videos_dir(&self) -> PathBuf {
         self.videos_dir.clone()
     }
 
-    /// 특정 비디오 파일 경로
-    pub fn video_path(&self, video_id: &str) -> PathBuf {
-        self.videos_dir().join(format!("{}.webm", video_id))
+    /// 캐시 카탈로그
+    pub fn library(&self) -> &VideoLibrary {
+        &self.library
+    }
+
+    /// yt-dlp info.json 임시 저장 디렉토리
+    fn info_dir(&self) -> PathBuf {
+        self.data_dir.join("info")
+    }
+
+    /// 캐시 키에 해당하는 다운로드 완료 파일 찾기 (확장자는 포맷에 따라 다름)
+    pub fn cached_file(&self, cache_key: &str) -> Option<PathBuf> {
+        let entries = std::fs::read_dir(self.videos_dir()).ok()?;
+        entries.flatten().map(|entry| entry.path()).find(|path| {
+            path.is_file()
+                && path.file_stem().and_then(|s| s.to_str()) == Some(cache_key)
+                && !is_incomplete_file(path)
+        })
+    }
+
+    /// 스트리밍 다운로드 중인 파일 찾기
+    pub fn live_file(&self, cache_key: &str) -> Option<PathBuf> {
+        let live_stem = format!("{}.{}", cache_key, LIVE_FILE_MARKER);
+        let entries = std::fs::read_dir(self.videos_dir()).ok()?;
+        entries.flatten().map(|entry| entry.path()).find(|path| {
+            path.is_file() && path.file_stem().and_then(|s| s.to_str()) == Some(live_stem.as_str())
+        })
     }
 
     /// 설치된 브라우저 감지 (Windows)
@@ -335,22 +1044,6 @@ impl YtDlpManager {
         installed
     }
 
-    /// 에러 메시지가 성인인증 관련인지 확인
-    fn is_age_restriction_error(error_msg: &str) -> bool {
-        error_msg.contains("Sign in to confirm your age")
-            || error_msg.contains("age-restricted")
-            || error_msg.contains("confirm your age")
-            || error_msg.contains("inappropriate for some users")
-            || error_msg.contains("--cookies-from-browser")
-    }
-
-    /// 에러 메시지가 DPAPI 복호화 실패인지 확인 (Windows Chrome/Edge 쿠키 문제)
-    fn is_dpapi_error(error_msg: &str) -> bool {
-        error_msg.contains("Failed to decrypt with DPAPI")
-            || error_msg.contains("failed to decrypt")
-            || error_msg.contains("DPAPI")
-    }
-
     /// Deno 런타임 설치 (Windows)
     #[cfg(windows)]
     async fn ensure_deno(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
@@ -360,206 +1053,556 @@ impl YtDlpManager {
         }
 
         tracing::info!("Downloading Deno runtime...");
-        let url = "https://github.com/denoland/deno/releases/latest/download/deno-x86_64-pc-windows-msvc.zip";
-
-        let response = self
-            .client
-            .get(url)
-            .header("User-Agent", "ivLyrics-helper")
-            .send()
+        let (_, assets) = self.fetch_latest_release("denoland/deno").await?;
+        let asset = assets
+            .iter()
+            .find(|asset| asset.name == "deno-x86_64-pc-windows-msvc.zip")
+            .ok_or("Deno for Windows not found in release")?;
+
+        // 압축 파일은 임시 파일로 받아 확인 후 압축 해제
+        let client = self.http_client();
+        let zip_path = self.data_dir.join(format!("{}.part", asset.name));
+        let sha256 = self
+            .binary_downloads
+            .download_to_file(&client, &asset.browser_download_url, &asset.name, &zip_path)
             .await?;
-
-        let bytes = response.bytes().await?;
+        // 체크섬이 맞지 않거나 확인할 수 없으면 설치하지 않음
+        if let Err(e) = checksum::verify_asset(&client, &assets, asset, &sha256)
+            .await
+            .ensure_verified()
+        {
+            let _ = tokio::fs::remove_file(&zip_path).await;
+            self.binary_downloads.set_state(
+                &asset.name,
+                BinaryDownloadState::Failed,
+                Some(e.clone()),
+            );
+            return Err(e.into());
+        }
         let data_dir = self.data_dir.clone();
+        let archive_path = zip_path.clone();
 
         // Blocking 작업 (압축 해제)
-        tokio::task::spawn_blocking(
+        let extracted = tokio::task::spawn_blocking(
             move || -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-                use std::io::Cursor;
-
-                let reader = Cursor::new(bytes);
+                let reader = std::fs::File::open(&archive_path)?;
                 let mut archive = zip::ZipArchive::new(reader)?;
 
-                // deno.exe 추출
+                // deno.exe 추출 (중간에 실패해도 설치된 것으로 보이지 않도록 임시 파일 사용)
                 let mut file = archive.by_name("deno.exe")?;
-                let mut out_file = std::fs::File::create(data_dir.join("deno.exe"))?;
+                let temp_path = data_dir.join("deno.exe.part");
+                let mut out_file = std::fs::File::create(&temp_path)?;
                 std::io::copy(&mut file, &mut out_file)?;
+                drop(out_file);
+                std::fs::rename(&temp_path, data_dir.join("deno.exe"))?;
 
                 Ok(())
             },
         )
-        .await??;
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|result| result.map_err(|e| e.to_string()));
+        let _ = tokio::fs::remove_file(&zip_path).await;
+        let (state, error) = match &extracted {
+            Ok(()) => (BinaryDownloadState::Completed, None),
+            Err(e) => (BinaryDownloadState::Failed, Some(e.clone())),
+        };
+        self.binary_downloads.set_state(&asset.name, state, error);
+        extracted?;
 
         tracing::info!("Deno downloaded successfully to {:?}", deno_path);
         Ok(())
     }
 
     /// yt-dlp가 존재하는지 확인하고, 없으면 다운로드
+    /// (설치 후 업데이트는 run_update_checks에서 주기적으로 확인)
     pub async fn ensure_ytdlp(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         // 디렉토리 생성
         tokio::fs::create_dir_all(&self.data_dir).await?;
         tokio::fs::create_dir_all(self.videos_dir()).await?;
 
-        let ytdlp_path = self.ytdlp_path();
+        let config = self.read_config().await.unwrap_or_default();
+        let detected = self.detect_binaries().await;
 
+        // 시스템/지정 경로의 Deno를 쓰도록 설정했다면 받지 않음
         #[cfg(windows)]
         {
-            if let Err(e) = self.ensure_deno().await {
-                tracing::warn!("Failed to ensure Deno: {}", e);
+            let deno_missing = detected
+                .deno
+                .as_ref()
+                .and_then(BinaryStatus::usable_path)
+                .is_none();
+            if deno_missing
+                && matches!(
+                    config.denoLocation,
+                    BinaryLocation::Auto | BinaryLocation::Bundled
+                )
+            {
+                match self.ensure_deno().await {
+                    // 받은 Deno를 --js-runtimes에 사용하도록 다시 감지
+                    Ok(()) => {
+                        self.detect_binaries().await;
+                    }
+                    Err(e) => tracing::warn!("Failed to ensure Deno: {}", e),
+                }
             }
         }
 
-        if ytdlp_path.exists() {
-            tracing::info!("yt-dlp already exists at {:?}", ytdlp_path);
-            // 업데이트 체크는 나중에 추가 가능
-            return Ok(());
+        if let Some(status) = &detected.ytdlp {
+            if let Some(path) = status.usable_path() {
+                tracing::info!("yt-dlp already exists at {:?}", path);
+                return Ok(());
+            }
+            // 시스템/지정 경로는 앱이 대신 받지 않음
+            if matches!(
+                config.ytdlpLocation,
+                BinaryLocation::System | BinaryLocation::Custom(_)
+            ) {
+                return Err(status
+                    .error
+                    .clone()
+                    .unwrap_or_else(|| "yt-dlp not found".to_string())
+                    .into());
+            }
         }
 
         tracing::info!("Downloading yt-dlp...");
+        self.update_ytdlp(true).await?;
+        tracing::info!(
+            "yt-dlp downloaded successfully to {:?}",
+            self.bundled_ytdlp_path()
+        );
+
+        Ok(())
+    }
+
+    /// 최신 릴리스의 태그와 asset 목록 조회 (설정된 미러가 있으면 미러 사용)
+    async fn fetch_latest_release(
+        &self,
+        repo: &str,
+    ) -> Result<(String, Vec<ReleaseAsset>), String> {
+        let config = self.read_config().await.unwrap_or_default();
+        let api_base = match config.releaseApiBaseUrl.trim().trim_end_matches('/') {
+            "" => GITHUB_API_BASE_URL,
+            base => base,
+        };
+        let url = format!("{}/repos/{}/releases/latest", api_base, repo);
 
-        // GitHub API에서 최신 릴리즈 정보 가져오기
-        let release_info: serde_json::Value = self
-            .client
-            .get("https://api.github.com/repos/yt-dlp/yt-dlp/releases/latest")
-            .header("User-Agent", "ivLyrics-helper")
+        let response = self
+            .http_client()
+            .get(&url)
             .send()
-            .await?
-            .json()
-            .await?;
+            .await
+            .map_err(|e| format!("Failed to reach {}: {}", api_base, e))?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "{} responded with status {}",
+                api_base,
+                response.status()
+            ));
+        }
+        let release_info: serde_json::Value = response.json().await.map_err(|e| e.to_string())?;
+
+        let version = release_info["tag_name"]
+            .as_str()
+            .ok_or("No tag_name in release")?
+            .to_string();
+        let mut assets: Vec<ReleaseAsset> = serde_json::from_value(release_info["assets"].clone())
+            .map_err(|e| format!("No assets found: {}", e))?;
+
+        // 미러가 GitHub 다운로드 주소를 그대로 돌려주는 경우 다운로드도 미러로
+        let download_base = config.releaseDownloadBaseUrl.trim().trim_end_matches('/');
+        if !download_base.is_empty() {
+            for asset in &mut assets {
+                if let Some(rest) = asset
+                    .browser_download_url
+                    .strip_prefix(GITHUB_DOWNLOAD_BASE_URL)
+                {
+                    asset.browser_download_url = format!("{}{}", download_base, rest);
+                }
+            }
+        }
 
-        // 플랫폼에 맞는 실행 파일 URL 찾기
-        let assets = release_info["assets"].as_array().ok_or("No assets found")?;
-        let binary_name = Self::get_ytdlp_binary_name();
+        Ok((version, assets))
+    }
 
-        let download_url = assets
+    /// 최신 릴리스와 현재 플랫폼용 실행 파일 URL 조회
+    async fn latest_ytdlp_release(&self) -> Result<YtDlpRelease, String> {
+        let (version, assets) = self.fetch_latest_release("yt-dlp/yt-dlp").await?;
+        let binary_name = Self::get_ytdlp_binary_name();
+        let asset = assets
             .iter()
-            .find(|asset| {
-                asset["name"]
-                    .as_str()
-                    .map(|n| n == binary_name)
-                    .unwrap_or(false)
-            })
-            .and_then(|asset| asset["browser_download_url"].as_str())
+            .find(|asset| asset.name == binary_name)
+            .cloned()
             .ok_or_else(|| format!("{} not found in release", binary_name))?;
 
-        tracing::info!("Downloading from: {}", download_url);
+        Ok(YtDlpRelease {
+            version,
+            asset,
+            assets,
+        })
+    }
 
-        // 다운로드
-        let response = self.client.get(download_url).send().await?;
-        let bytes = response.bytes().await?;
+    /// 마지막으로 확인한 버전 정보
+    pub fn ytdlp_version(&self) -> YtDlpVersion {
+        self.version
+            .lock()
+            .map(|version| version.clone())
+            .unwrap_or_default()
+    }
 
-        // 파일 저장
-        tokio::fs::write(&ytdlp_path, bytes).await?;
+    /// 확인 결과 저장 (체크섬 확인 결과는 새로 받은 파일이 있을 때만 바뀜)
+    fn set_version(&self, mut version: YtDlpVersion) -> YtDlpVersion {
+        if let Ok(mut current) = self.version.lock() {
+            if version.verification.is_none() {
+                version.verification = current.verification.take();
+            }
+            *current = version.clone();
+        }
+        version
+    }
+
+    /// 설치된 버전과 최신 릴리스 비교
+    pub async fn check_ytdlp_version(&self) -> YtDlpVersion {
+        let installed = binaries::probe_version(Binary::YtDlp, &self.ytdlp_path())
+            .await
+            .ok();
+        let (latest, error) = match self.latest_ytdlp_release().await {
+            Ok(release) => (Some(release.version), None),
+            Err(e) => (None, Some(e)),
+        };
+        self.set_version(YtDlpVersion {
+            update_available: is_newer_version(installed.as_deref(), latest.as_deref()),
+            installed,
+            latest,
+            checked_at: Some(crate::video_library::now_secs()),
+            error,
+            verification: None,
+        })
+    }
+
+    /// 최신 릴리스로 yt-dlp 교체 (force가 아니면 이미 최신일 때 건너뜀)
+    ///
+    /// 새 파일을 옆에 받아 `--version`으로 확인한 뒤 교체하고,
+    /// 교체한 파일이 실행되지 않으면 이전 파일로 되돌림
+    /// 시스템/지정 경로의 yt-dlp는 패키지 관리자 등이 관리하므로 건드리지 않음
+    pub async fn update_ytdlp(&self, force: bool) -> Result<YtDlpVersion, String> {
+        if let Some(status) = self.binaries().await.ytdlp {
+            if let Some(path) = status.path.filter(|_| !status.bundled) {
+                return Err(format!("yt-dlp at {:?} is managed outside the app", path));
+            }
+        }
+
+        let _guard = self.install_lock.lock().await;
+        tokio::fs::create_dir_all(&self.data_dir)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let ytdlp_path = self.bundled_ytdlp_path();
+        let installed = binaries::probe_version(Binary::YtDlp, &ytdlp_path)
+            .await
+            .ok();
+        let release = match self.latest_ytdlp_release().await {
+            Ok(release) => release,
+            Err(e) => {
+                let mut version = self.ytdlp_version();
+                version.installed = installed;
+                version.error = Some(e.clone());
+                self.set_version(version);
+                return Err(e);
+            }
+        };
+
+        let mut verification = None;
+        let result = if force || is_newer_version(installed.as_deref(), Some(&release.version)) {
+            let result = self.replace_ytdlp(&release, &mut verification).await;
+            let (state, error) = match &result {
+                Ok(_) => (BinaryDownloadState::Completed, None),
+                Err(e) => (BinaryDownloadState::Failed, Some(e.clone())),
+            };
+            self.binary_downloads
+                .set_state(&release.asset.name, state, error);
+            result
+        } else {
+            Ok(installed.clone().unwrap_or_default())
+        };
+
+        let installed = match &result {
+            Ok(version) => Some(version.clone()),
+            Err(_) => installed,
+        };
+        if result.is_ok() {
+            self.detect_binaries().await;
+        }
+        let version = self.set_version(YtDlpVersion {
+            update_available: is_newer_version(installed.as_deref(), Some(&release.version)),
+            installed,
+            latest: Some(release.version),
+            checked_at: Some(crate::video_library::now_secs()),
+            error: result.as_ref().err().cloned(),
+            verification,
+        });
+        result.map(|_| version)
+    }
+
+    /// 새 실행 파일을 받아 검증 후 교체 (실패하면 이전 파일 복원), 설치된 버전 반환
+    ///
+    /// 릴리스에 공개된 SHA-256과 다르거나 확인할 수 없으면 설치하지 않음
+    async fn replace_ytdlp(
+        &self,
+        release: &YtDlpRelease,
+        verification: &mut Option<Verification>,
+    ) -> Result<String, String> {
+        let ytdlp_path = self.bundled_ytdlp_path();
+        let file_name = ytdlp_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("yt-dlp")
+            .to_string();
+        let new_path = self.data_dir.join(format!("{}.new", file_name));
+        let backup_path = self.data_dir.join(format!("{}.old", file_name));
+
+        tracing::info!(
+            "Downloading yt-dlp {} from: {}",
+            release.version,
+            release.asset.browser_download_url
+        );
+        // 기존 파일 옆에 나눠 받아서, 중간에 끊겨도 사용 중인 yt-dlp는 그대로
+        let client = self.http_client();
+        let sha256 = self
+            .binary_downloads
+            .download_to_file(
+                &client,
+                &release.asset.browser_download_url,
+                &release.asset.name,
+                &new_path,
+            )
+            .await?;
+        let checked =
+            checksum::verify_asset(&client, &release.assets, &release.asset, &sha256).await;
+        let verified = checked.ensure_verified();
+        *verification = Some(checked);
+        if let Err(e) = verified {
+            let _ = tokio::fs::remove_file(&new_path).await;
+            return Err(e);
+        }
 
         // macOS/Linux에서는 실행 권한 부여
         #[cfg(unix)]
         {
             use std::os::unix::fs::PermissionsExt;
-            let mut perms = tokio::fs::metadata(&ytdlp_path).await?.permissions();
-            perms.set_mode(0o755);
-            tokio::fs::set_permissions(&ytdlp_path, perms).await?;
+            let _ =
+                tokio::fs::set_permissions(&new_path, std::fs::Permissions::from_mode(0o755)).await;
+        }
+
+        if let Err(e) = binaries::probe_version(Binary::YtDlp, &new_path).await {
+            let _ = tokio::fs::remove_file(&new_path).await;
+            return Err(format!("Downloaded yt-dlp failed the smoke test: {}", e));
+        }
+
+        // 기존 파일은 백업해 두었다가 새 파일이 정상 실행되면 삭제
+        match install_with_backup(Binary::YtDlp, &new_path, &ytdlp_path, &backup_path).await {
+            Ok((version, backup)) => {
+                if let Some(backup) = backup {
+                    let _ = tokio::fs::remove_file(backup).await;
+                }
+                tracing::info!("yt-dlp updated to {}", version);
+                Ok(version)
+            }
+            Err(e) => {
+                let _ = tokio::fs::remove_file(&new_path).await;
+                tracing::warn!("yt-dlp update rolled back: {}", e);
+                Err(format!("yt-dlp update rolled back: {}", e))
+            }
+        }
+    }
+
+    /// 오프라인 번들(zip)에서 yt-dlp/ffmpeg/deno를 앱 폴더에 설치
+    ///
+    /// 번들에는 실행 파일과 함께 `SHA2-256SUMS`(sha256sum 형식)가 있어야 하고,
+    /// 해시가 맞지 않거나 실행되지 않는 파일이 하나라도 있으면 아무것도 설치하지 않음
+    /// 교체 도중 실패하면 이미 교체한 파일도 이전 파일로 되돌림
+    pub async fn install_bundle(&self, archive: &Path) -> Result<Binaries, String> {
+        let _guard = self.install_lock.lock().await;
+        tokio::fs::create_dir_all(&self.data_dir)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let archive = archive.to_path_buf();
+        let data_dir = self.data_dir.clone();
+        let (files, sums) =
+            tokio::task::spawn_blocking(move || extract_bundle(&archive, &data_dir))
+                .await
+                .map_err(|e| e.to_string())??;
+
+        let result = self.install_bundle_files(&files, sums.as_deref()).await;
+        for file in &files {
+            let _ = tokio::fs::remove_file(&file.staged).await;
+        }
+        result?;
+
+        Ok(self.detect_binaries().await)
+    }
+
+    /// 풀어 둔 실행 파일 확인 후 교체
+    async fn install_bundle_files(
+        &self,
+        files: &[BundleFile],
+        sums: Option<&str>,
+    ) -> Result<(), String> {
+        if files.is_empty() {
+            return Err("No yt-dlp, ffmpeg or deno executable found in bundle".to_string());
+        }
+
+        for file in files {
+            checksum::verify_with_sums(sums, &file.name, &file.sha256).ensure_verified()?;
         }
 
-        tracing::info!("yt-dlp downloaded successfully to {:?}", ytdlp_path);
+        // 모두 실행해 본 뒤에 교체
+        for file in files {
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                let _ = tokio::fs::set_permissions(
+                    &file.staged,
+                    std::fs::Permissions::from_mode(0o755),
+                )
+                .await;
+            }
+            binaries::probe_version(file.binary, &file.staged)
+                .await
+                .map_err(|e| format!("{} from bundle failed the smoke test: {}", file.name, e))?;
+        }
+
+        // 기존 파일은 `.old`로 옮겨 두고, 하나라도 실패하면 모두 되돌림
+        let mut installed: Vec<(PathBuf, Option<PathBuf>)> = Vec::new();
+        for file in files {
+            let target = self.data_dir.join(file.binary.file_name());
+            let backup = self
+                .data_dir
+                .join(format!("{}.old", file.binary.file_name()));
+            let result = install_with_backup(file.binary, &file.staged, &target, &backup).await;
+            match result {
+                Ok((_, backup)) => installed.push((target, backup)),
+                Err(e) => {
+                    for (target, backup) in installed.iter().rev() {
+                        let _ = tokio::fs::remove_file(target).await;
+                        if let Some(backup) = backup {
+                            let _ = tokio::fs::rename(backup, target).await;
+                        }
+                    }
+                    tracing::warn!("Offline bundle install rolled back: {}", e);
+                    return Err(format!("Failed to install {:?}: {}", target, e));
+                }
+            }
+        }
 
+        for (target, backup) in installed {
+            if let Some(backup) = backup {
+                let _ = tokio::fs::remove_file(backup).await;
+            }
+            tracing::info!("Installed {:?} from offline bundle", target);
+        }
         Ok(())
     }
 
-    /// 비디오가 이미 존재하는지 확인
-    pub fn video_exists(&self, video_id: &str) -> bool {
-        self.video_path(video_id).exists()
+    /// 주기적으로 새 yt-dlp 릴리스 확인 (ytdlpAutoUpdate면 바로 업데이트)
+    /// 앱이 받은 yt-dlp를 쓸 때만 확인
+    pub async fn run_update_checks(self) {
+        tokio::time::sleep(UPDATE_CHECK_DELAY).await;
+        loop {
+            let config = self.read_config().await.unwrap_or_default();
+            let bundled = self
+                .binaries()
+                .await
+                .ytdlp
+                .is_some_and(|status| status.bundled && status.compatible);
+            if bundled {
+                let version = self.check_ytdlp_version().await;
+                if version.update_available && config.ytdlpAutoUpdate {
+                    if let Err(e) = self.update_ytdlp(false).await {
+                        tracing::warn!("Failed to update yt-dlp: {}", e);
+                    }
+                } else if let Some(e) = version.error {
+                    tracing::warn!("Failed to check yt-dlp version: {}", e);
+                }
+            }
+
+            let hours = config.ytdlpUpdateIntervalHours.max(1) as u64;
+            tokio::time::sleep(Duration::from_secs(hours * 60 * 60)).await;
+        }
     }
 
     /// 비디오 다운로드 (진행 상황을 broadcast 채널로 전송)
     pub async fn download_video(
         &self,
-        video_id: &str,
+        request: &DownloadRequest,
         progress_tx: broadcast::Sender<DownloadProgress>,
-    ) -> Result<PathBuf, Box<dyn std::error::Error + Send + Sync>> {
-        let video_path = self.video_path(video_id);
+    ) -> Result<PathBuf, DownloadError> {
+        let video_id = request.video_id.as_str();
         let video_id_owned = video_id.to_string();
 
         // 이미 존재하면 바로 반환
-        if video_path.exists() {
+        if let Some(video_path) = self.cached_file(&request.cache_key()) {
+            if let Some(track) = &request.track {
+                self.library
+                    .set_track_if_missing(&request.cache_key(), track);
+            }
             let _ = progress_tx.send(DownloadProgress {
                 video_id: video_id_owned,
                 status: DownloadStatus::AlreadyExists,
                 percent: Some(100.0),
                 speed: None,
                 eta: None,
+                transfer: None,
+                error_code: None,
                 message: Some("Video already downloaded".to_string()),
             });
             return Ok(video_path);
         }
 
         // 쿠키 없이 먼저 시도
-        let result = self
-            .try_download_video(video_id, &progress_tx, None, None)
-            .await;
+        let result = self.try_download_video(request, &progress_tx, None).await;
 
         match result {
             Ok(path) => Ok(path),
             Err(e) => {
-                let error_msg = e.to_string();
-
                 // 성인인증 에러인 경우 쿠키로 재시도
-                if Self::is_age_restriction_error(&error_msg) {
+                if e.cookies_may_help() {
                     tracing::info!("Age restriction detected, attempting to use cookies...");
 
-                    // 1. 먼저 cookies.txt 파일로 시도 (설정에서 지정한 경우)
-                    let cookies_file = self.get_cookies_file_path().await;
-                    if let Some(ref cookies_path) = cookies_file {
-                        if std::path::Path::new(cookies_path).exists() {
-                            tracing::info!("Trying with cookies.txt file: {}", cookies_path);
-
-                            let _ = progress_tx.send(DownloadProgress {
-                                video_id: video_id_owned.clone(),
-                                status: DownloadStatus::Checking,
-                                percent: Some(0.0),
-                                speed: None,
-                                eta: None,
-                                message: Some("Trying with cookies.txt file...".to_string()),
-                            });
-
-                            match self
-                                .try_download_video(
-                                    video_id,
-                                    &progress_tx,
-                                    None,
-                                    Some(cookies_path.as_str()),
-                                )
-                                .await
-                            {
-                                Ok(path) => {
-                                    tracing::info!("Successfully downloaded with cookies.txt");
-                                    return Ok(path);
-                                }
-                                Err(cookies_err) => {
-                                    tracing::warn!("Failed with cookies.txt: {}", cookies_err);
-                                }
-                            }
-                        }
-                    }
-
-                    // 2. 브라우저 쿠키로 시도
-                    let installed_browsers = Self::detect_installed_browsers();
-
-                    if installed_browsers.is_empty() && cookies_file.is_none() {
-                        tracing::warn!("No supported browsers or cookies.txt found");
+                    // 설정에 따라 쿠키 출처를 정하고, 마지막으로 성공한 출처부터 시도
+                    let config = self.read_config().await.unwrap_or_default();
+                    let installed_browsers = if config.cookiesAutoProbe {
+                        Self::detect_installed_browsers()
+                    } else {
+                        Vec::new()
+                    };
+                    let sources = cookies::candidates(
+                        &config,
+                        &installed_browsers,
+                        self.cookie_state.last_working().as_ref(),
+                    );
+
+                    if sources.is_empty() {
+                        tracing::warn!(
+                            "No cookies.txt, configured browser or supported browsers found"
+                        );
                         let _ = progress_tx.send(DownloadProgress {
                             video_id: video_id_owned.clone(),
                             status: DownloadStatus::Error,
                             percent: None,
                             speed: None,
                             eta: None,
-                            message: Some("Age-restricted video. No cookies.txt or supported browsers found. Please set a cookies.txt file in Settings.".to_string()),
+                            transfer: None,
+                            error_code: Some(e.code),
+                            message: Some("Age-restricted video. No cookies.txt or supported browsers found. Please set a cookies.txt file or a browser in Settings.".to_string()),
                         });
                         return Err(e);
                     }
 
-                    // 각 브라우저로 순차적으로 시도
-                    for browser in installed_browsers {
-                        tracing::info!("Trying with browser cookies: {}", browser);
+                    for source in &sources {
+                        tracing::info!("Trying with cookies from {}", source.label());
 
                         let _ = progress_tx.send(DownloadProgress {
                             video_id: video_id_owned.clone(),
@@ -567,31 +1610,42 @@ impl YtDlpManager {
                             percent: Some(0.0),
                             speed: None,
                             eta: None,
-                            message: Some(format!("Trying with {} cookies...", browser)),
+                            transfer: None,
+                            error_code: None,
+                            message: Some(format!(
+                                "Trying with cookies from {}...",
+                                source.label()
+                            )),
                         });
 
                         match self
-                            .try_download_video(video_id, &progress_tx, Some(browser), None)
+                            .try_download_video(request, &progress_tx, Some(source))
                             .await
                         {
                             Ok(path) => {
-                                tracing::info!("Successfully downloaded with {} cookies", browser);
+                                tracing::info!(
+                                    "Successfully downloaded with cookies from {}",
+                                    source.label()
+                                );
+                                self.cookie_state.remember(source);
                                 return Ok(path);
                             }
-                            Err(browser_err) => {
-                                let err_msg = browser_err.to_string();
-                                if Self::is_dpapi_error(&err_msg)
-                                    || Self::is_cookie_db_error(&err_msg)
-                                {
-                                    tracing::warn!("Cookie extraction failed for {} (Chromium security). Trying next browser...", browser);
+                            Err(cookies_err) => {
+                                if cookies_err.code == DownloadErrorCode::CookieExtractionFailed {
+                                    tracing::warn!("Cookie extraction failed for {} (Chromium security). Trying next source...", source.label());
                                 } else {
                                     tracing::warn!(
-                                        "Failed with {} cookies: {}",
-                                        browser,
-                                        browser_err
+                                        "Failed with cookies from {}: {}",
+                                        source.label(),
+                                        cookies_err
                                     );
                                 }
-                                // 다음 브라우저로 계속 시도
+                                // 쿠키 문제로 실패했으면 다음부터 이 출처를 먼저 시도하지 않음
+                                if cookies_err.code == DownloadErrorCode::CookieExtractionFailed
+                                    || cookies_err.cookies_may_help()
+                                {
+                                    self.cookie_state.forget(source);
+                                }
                             }
                         }
                     }
@@ -603,12 +1657,15 @@ impl YtDlpManager {
                         percent: None,
                         speed: None,
                         eta: None,
+                        transfer: None,
+                        error_code: Some(e.code),
                         message: Some("Age-restricted video. Please set a valid cookies.txt file in Settings. See the help (?) for instructions.".to_string()),
                     });
-                    Err(
-                        "Failed to download age-restricted video. Please configure cookies.txt file."
-                            .into(),
+                    Err(DownloadError::new(
+                        e.code,
+                        "Failed to download age-restricted video. Please configure cookies.txt file.",
                     )
+                    .with_cookies_tried())
                 } else {
                     Err(e)
                 }
@@ -616,43 +1673,150 @@ impl YtDlpManager {
         }
     }
 
-    /// cookies.txt 파일 경로 가져오기 (설정에서)
-    async fn get_cookies_file_path(&self) -> Option<String> {
-        let config_path = self.data_dir.join("config.json");
-        if let Ok(content) = tokio::fs::read(&config_path).await {
-            if let Ok(cfg) = serde_json::from_slice::<crate::config::AppConfig>(&content) {
-                if !cfg.cookiesFile.is_empty() {
-                    return Some(cfg.cookiesFile);
-                }
+    /// yt-dlp를 실행하고 stdout 전체를 반환 (실패 시 stderr를 에러로)
+    async fn run_ytdlp(
+        &self,
+        args: &[String],
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let config = self.read_config().await.unwrap_or_default();
+        let mut cmd = Command::new(self.ytdlp_path());
+        cmd.args(network::ytdlp_args(&config))
+            .args(args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        #[cfg(windows)]
+        {
+            cmd.creation_flags(CREATE_NO_WINDOW);
+        }
+
+        let output = cmd.output().await?;
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stderr = stderr.trim();
+            if stderr.is_empty() {
+                Err(format!("yt-dlp exited with status: {}", output.status).into())
+            } else {
+                Err(format!("ERROR: {}", stderr).into())
+            }
+        }
+    }
+
+    /// 다운로드 없이 비디오 메타데이터 조회
+    pub async fn fetch_video_info(
+        &self,
+        source: VideoSource,
+        video_id: &str,
+    ) -> Result<VideoInfo, Box<dyn std::error::Error + Send + Sync>> {
+        source.ensure_public_host(video_id).await?;
+        let url = source
+            .download_url(video_id)
+            .ok_or("Local videos have no online metadata")?;
+        let mut args = vec![
+            "--dump-json".to_string(),
+            "--skip-download".to_string(),
+            "--no-playlist".to_string(),
+        ];
+
+        args.extend(self.binary_args().await);
+
+        // 성인인증 영상은 cookies.txt가 있으면 사용
+        if let Some(cookies_path) = self.get_cookies_file_path().await {
+            if std::path::Path::new(&cookies_path).exists() {
+                args.push("--cookies".to_string());
+                args.push(cookies_path);
             }
         }
-        None
+        // id가 `-`로 시작해도 옵션으로 해석되지 않도록 `--` 뒤에 전달
+        args.push("--".to_string());
+        args.push(url);
+
+        match self.run_ytdlp(&args).await {
+            Ok(stdout) => {
+                let json: serde_json::Value = serde_json::from_str(stdout.trim())?;
+                Ok(VideoInfo::from_json(video_id, &json))
+            }
+            Err(e)
+                if DownloadErrorCode::classify(&e.to_string())
+                    == DownloadErrorCode::AgeRestricted =>
+            {
+                Ok(VideoInfo::age_restricted_only(video_id))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// YouTube 재생목록의 영상 목록 (--flat-playlist, 영상 정보는 조회하지 않음)
+    pub async fn playlist_entries(
+        &self,
+        playlist_id: &str,
+    ) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error + Send + Sync>> {
+        let args = vec![
+            "--flat-playlist".to_string(),
+            "--dump-json".to_string(),
+            "--".to_string(),
+            format!("https://www.youtube.com/playlist?list={}", playlist_id),
+        ];
+
+        let stdout = self.run_ytdlp(&args).await?;
+        Ok(stdout
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
     }
 
-    /// 에러 메시지가 쿠키 데이터베이스 복사 실패인지 확인
-    fn is_cookie_db_error(error_msg: &str) -> bool {
-        error_msg.contains("Could not copy Chrome cookie database")
-            || error_msg.contains("could not copy")
-            || error_msg.contains("cookie database")
+    /// YouTube 검색 (ytsearchN:) 결과를 평면 목록으로 반환
+    pub async fn search_videos(
+        &self,
+        query: &str,
+        count: usize,
+    ) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error + Send + Sync>> {
+        let args = vec![
+            "--flat-playlist".to_string(),
+            "--dump-json".to_string(),
+            "--".to_string(),
+            format!("ytsearch{}:{}", count, query),
+        ];
+
+        let stdout = self.run_ytdlp(&args).await?;
+        Ok(stdout
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+
+    /// 디스크에 저장된 설정 읽기 (ConfigManager와 별도로 최신 값을 사용)
+    pub async fn read_config(&self) -> Option<AppConfig> {
+        let config_path = self.data_dir.join("config.json");
+        let content = tokio::fs::read(&config_path).await.ok()?;
+        serde_json::from_slice::<AppConfig>(&content).ok()
+    }
+
+    /// cookies.txt 파일 경로 가져오기 (설정에서)
+    async fn get_cookies_file_path(&self) -> Option<String> {
+        self.read_config()
+            .await
+            .map(|cfg| cfg.cookiesFile)
+            .filter(|path| !path.is_empty())
     }
 
     /// 비디오 다운로드 시도 (브라우저 쿠키 또는 cookies.txt 파일 옵션 포함)
     async fn try_download_video(
         &self,
-        video_id: &str,
+        request: &DownloadRequest,
         progress_tx: &broadcast::Sender<DownloadProgress>,
-        browser: Option<&str>,
-        cookies_file: Option<&str>,
-    ) -> Result<PathBuf, Box<dyn std::error::Error + Send + Sync>> {
+        cookies: Option<&CookieSource>,
+    ) -> Result<PathBuf, DownloadError> {
+        let video_id = request.video_id.as_str();
         let video_id_owned = video_id.to_string();
+        let cache_key = request.cache_key();
 
         // 다운로드 상태 전송
-        let checking_msg = if cookies_file.is_some() {
-            "Checking video with cookies.txt...".to_string()
-        } else if let Some(b) = browser {
-            format!("Checking video with {} cookies...", b)
-        } else {
-            "Checking video availability...".to_string()
+        let checking_msg = match cookies {
+            Some(source) => format!("Checking video with cookies from {}...", source.label()),
+            None => "Checking video availability...".to_string(),
         };
 
         let _ = progress_tx.send(DownloadProgress {
@@ -661,38 +1825,94 @@ impl YtDlpManager {
             percent: Some(0.0),
             speed: None,
             eta: None,
+            transfer: None,
+            error_code: None,
             message: Some(checking_msg),
         });
 
-        let url = format!("https://www.youtube.com/watch?v={}", video_id);
-        let output_template = self.videos_dir().join("%(id)s.%(ext)s");
+        // 매핑/프리페치 등 HTTP 외 경로로 들어온 요청도 파일 이름/인자로 쓰기 전에 다시 확인
+        request.source.validate_id(video_id)?;
+        request.source.ensure_public_host(video_id).await?;
+        if !is_safe_cache_key(&cache_key) {
+            return Err(format!("Invalid cache key: {}", cache_key).into());
+        }
+        let url = request
+            .source
+            .download_url(video_id)
+            .ok_or("Local videos can't be downloaded")?;
+        // 캐시 키를 파일명으로 사용해 모드별로 따로 저장
+        // 스트리밍 다운로드는 완료 전까지 `.live` 이름으로 두어 완료 파일과 구분
+        let output_template = if request.live {
+            // 이전 시도에서 남은 파일이 있으면 이미 받은 것으로 착각하므로 삭제
+            if let Some(stale) = self.live_file(&cache_key) {
+                let _ = tokio::fs::remove_file(stale).await;
+            }
+            self.videos_dir()
+                .join(format!("{}.{}.%(ext)s", cache_key, LIVE_FILE_MARKER))
+        } else {
+            self.videos_dir().join(format!("{}.%(ext)s", cache_key))
+        };
+        // 카탈로그용 메타데이터는 비디오 폴더 밖에 info.json으로 기록
+        let info_template = self.info_dir().join(format!("{}.%(ext)s", cache_key));
 
         // yt-dlp 명령 구성
         let mut cmd = Command::new(self.ytdlp_path());
 
+        let format_selector = if request.live {
+            request.mode.live_format_selector()
+        } else {
+            request.mode.format_selector()
+        };
+
         let mut args = vec![
-            "-f".to_string(), 
-            "bestvideo[height<=1080][ext=webm]/bestvideo[height<=1080]/bestvideo[ext=webm]/bestvideo".to_string(),
+            "-f".to_string(),
+            format_selector.to_string(),
             "--no-playlist".to_string(),
             "--progress".to_string(),
             "--newline".to_string(),
+            // 사람이 읽는 [download] 줄 대신 JSON으로 진행 상황 출력
+            "--progress-template".to_string(),
+            PROGRESS_TEMPLATE.to_string(),
             // Restrict filenames to avoid Windows invalid character issues
             "--restrict-filenames".to_string(),
+            "--write-info-json".to_string(),
+            "--no-write-playlist-metafiles".to_string(),
         ];
 
-        // cookies.txt 파일 옵션 (우선)
-        if let Some(cookies_path) = cookies_file {
-            args.push("--cookies".to_string());
-            args.push(cookies_path.to_string());
+        // ffmpeg(병합/구간 추출), deno(YouTube 서명 해석) 위치
+        args.extend(self.binary_args().await);
+
+        // 프록시, 속도 제한 등 네트워크 설정
+        let config = self.read_config().await.unwrap_or_default();
+        args.extend(network::ytdlp_args(&config));
+
+        // 구간 다운로드 (인트로/아웃트로 제외)
+        if let Some(clip) = &request.clip {
+            args.push("--download-sections".to_string());
+            args.push(clip.download_section());
+        }
+
+        // .part 없이 최종 파일에 바로 기록해야 다운로드 중에도 읽을 수 있음
+        if request.live {
+            args.push("--no-part".to_string());
         }
-        // 브라우저 쿠키 옵션
-        else if let Some(browser_name) = browser {
-            args.push("--cookies-from-browser".to_string());
-            args.push(browser_name.to_string());
+
+        // 영상+오디오 병합 시 브라우저에서 재생 가능한 컨테이너로
+        if request.mode == DownloadMode::Muxed {
+            args.push("--merge-output-format".to_string());
+            args.push("mp4".to_string());
+        }
+
+        // cookies.txt 또는 브라우저 쿠키 옵션
+        if let Some(source) = cookies {
+            args.extend(source.ytdlp_args());
         }
 
         args.push("-o".to_string());
         args.push(output_template.to_str().unwrap().to_string());
+        args.push("-o".to_string());
+        args.push(format!("infojson:{}", info_template.to_string_lossy()));
+        args.push("--".to_string());
         args.push(url.clone());
 
         cmd.args(&args)
@@ -704,7 +1924,14 @@ impl YtDlpManager {
             cmd.creation_flags(CREATE_NO_WINDOW);
         }
 
-        let mut child = cmd.spawn()?;
+        let mut child = cmd.spawn().map_err(|e| {
+            let code = if e.kind() == std::io::ErrorKind::NotFound {
+                DownloadErrorCode::BinaryMissing
+            } else {
+                DownloadErrorCode::Unknown
+            };
+            DownloadError::new(code, format!("Failed to run yt-dlp: {}", e))
+        })?;
 
         let stdout = child.stdout.take().ok_or("Failed to get stdout")?;
         let stderr = child.stderr.take().ok_or("Failed to get stderr")?;
@@ -717,33 +1944,27 @@ impl YtDlpManager {
             let reader = BufReader::new(stdout);
             let mut lines = reader.lines();
 
-            let progress_regex = Regex::new(
-                r"\[download\]\s+(\d+\.?\d*)%\s+of\s+[\d.]+\w*\s+at\s+([\d.]+\w*/s)\s+ETA\s+(\S+)",
-            )
-            .ok();
-
             while let Ok(Some(line)) = lines.next_line().await {
-                tracing::debug!("yt-dlp stdout: {}", line);
-
-                if let Some(ref regex) = progress_regex {
-                    if let Some(caps) = regex.captures(&line) {
-                        let percent: f32 = caps
-                            .get(1)
-                            .and_then(|m| m.as_str().parse().ok())
-                            .unwrap_or(0.0);
-                        let speed = caps.get(2).map(|m| m.as_str().to_string());
-                        let eta = caps.get(3).map(|m| m.as_str().to_string());
-
-                        let _ = progress_tx_clone.send(DownloadProgress {
-                            video_id: video_id_for_stdout.clone(),
-                            status: DownloadStatus::Downloading,
-                            percent: Some(percent),
-                            speed,
-                            eta,
-                            message: Some(format!("Downloading: {:.1}%", percent)),
-                        });
-                    }
+                if let Some(transfer) = TransferProgress::parse_line(&line) {
+                    let percent = transfer.percent();
+                    let message = match (percent, transfer.downloaded_bytes) {
+                        (Some(percent), _) => format!("Downloading: {:.1}%", percent),
+                        (None, Some(done)) => format!("Downloading: {}", format_bytes(done as f64)),
+                        (None, None) => "Downloading...".to_string(),
+                    };
+                    let _ = progress_tx_clone.send(DownloadProgress {
+                        video_id: video_id_for_stdout.clone(),
+                        status: DownloadStatus::Downloading,
+                        percent,
+                        speed: transfer.speed_text(),
+                        eta: transfer.eta_text(),
+                        message: Some(message),
+                        transfer: Some(transfer),
+                        error_code: None,
+                    });
+                    continue;
                 }
+                tracing::debug!("yt-dlp stdout: {}", line);
 
                 if line.contains("[Merger]")
                     || line.contains("[ExtractAudio]")
@@ -755,6 +1976,8 @@ impl YtDlpManager {
                         percent: Some(99.0),
                         speed: None,
                         eta: None,
+                        transfer: None,
+                        error_code: None,
                         message: Some("Processing...".to_string()),
                     });
                 }
@@ -784,28 +2007,28 @@ impl YtDlpManager {
         let _ = stdout_handle.await;
 
         // stderr 내용 가져오기
-        let (_, stderr_lines) = stderr_content.await?;
+        let (_, stderr_lines) = stderr_content.await.map_err(|e| e.to_string())?;
         let combined_stderr = stderr_lines.join("\n");
 
         if status.success() {
-            // 다운로드된 파일 찾기
-            let videos_dir = self.videos_dir();
-            let mut found_path = None;
-
-            if let Ok(mut entries) = tokio::fs::read_dir(&videos_dir).await {
-                while let Ok(Some(entry)) = entries.next_entry().await {
-                    let file_name = entry.file_name();
-                    let file_name_str = file_name.to_string_lossy();
-                    if file_name_str.starts_with(video_id) {
-                        found_path = Some(entry.path());
-                        break;
-                    }
-                }
+            // 스트리밍 파일을 완료 파일 이름으로 변경 (읽고 있는 핸들은 그대로 유지됨)
+            if let Some(live_path) = self.live_file(&cache_key) {
+                let extension = live_path
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .unwrap_or("webm");
+                let final_path = self
+                    .videos_dir()
+                    .join(format!("{}.{}", cache_key, extension));
+                tokio::fs::rename(&live_path, &final_path).await?;
             }
 
-            if let Some(path) = found_path {
+            // 다운로드된 파일 찾기
+            if let Some(path) = self.cached_file(&cache_key) {
                 let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
 
+                self.record_library_entry(request, file_name, &path).await;
+
                 // Cache pruning (best effort)
                 if let Err(e) = self.prune_cache_if_needed().await {
                     tracing::warn!("Failed to prune cache: {}", e);
@@ -817,6 +2040,8 @@ impl YtDlpManager {
                     percent: Some(100.0),
                     speed: None,
                     eta: None,
+                    transfer: None,
+                    error_code: None,
                     message: Some(format!("http://localhost:15123/video/files/{}", file_name)),
                 });
                 Ok(path)
@@ -824,11 +2049,19 @@ impl YtDlpManager {
                 Err("Downloaded file not found".into())
             }
         } else {
-            // 에러 발생 시 stderr 내용을 에러로 반환
-            let error_msg = if !combined_stderr.is_empty() {
-                format!("ERROR: {}", combined_stderr)
+            // 중간에 실패한 스트리밍 파일은 완료 파일로 오인되지 않도록 정리
+            if let Some(live_path) = self.live_file(&cache_key) {
+                let _ = tokio::fs::remove_file(live_path).await;
+            }
+
+            // 에러 발생 시 stderr 내용으로 원인을 분류해 반환
+            let error = if !combined_stderr.is_empty() {
+                DownloadError::from_stderr(&combined_stderr)
             } else {
-                format!("yt-dlp exited with status: {}", status)
+                DownloadError::new(
+                    DownloadErrorCode::Unknown,
+                    format!("yt-dlp exited with status: {}", status),
+                )
             };
 
             let _ = progress_tx.send(DownloadProgress {
@@ -837,62 +2070,249 @@ impl YtDlpManager {
                 percent: None,
                 speed: None,
                 eta: None,
-                message: Some(error_msg.clone()),
+                transfer: None,
+                error_code: Some(error.code),
+                message: Some(error.message.clone()),
             });
 
-            Err(error_msg.into())
+            Err(error)
+        }
+    }
+
+    /// 다운로드 완료된 비디오를 카탈로그에 기록 (info.json은 읽은 뒤 삭제)
+    async fn record_library_entry(
+        &self,
+        request: &DownloadRequest,
+        file_name: &str,
+        path: &std::path::Path,
+    ) {
+        let cache_key = request.cache_key();
+        let info_path = self.info_dir().join(format!("{}.info.json", cache_key));
+        let info = match tokio::fs::read(&info_path).await {
+            Ok(content) => serde_json::from_slice(&content).unwrap_or_default(),
+            Err(e) => {
+                tracing::warn!("Failed to read info.json for {}: {}", cache_key, e);
+                serde_json::Value::Null
+            }
+        };
+        let _ = tokio::fs::remove_file(&info_path).await;
+
+        let filesize = tokio::fs::metadata(path)
+            .await
+            .map(|m| m.len())
+            .unwrap_or(0);
+        self.library.upsert(entry_from_info_json(
+            &cache_key, request, file_name, filesize, &info,
+        ));
+    }
+
+    /// 비디오 폴더의 완료된 파일 총 크기
+    pub async fn cache_usage(&self) -> u64 {
+        let Ok(mut entries) = tokio::fs::read_dir(self.videos_dir()).await else {
+            return 0;
+        };
+        let mut total: u64 = 0;
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            if is_incomplete_file(&entry.path()) {
+                continue;
+            }
+            if let Ok(metadata) = entry.metadata().await {
+                if metadata.is_file() {
+                    total = total.saturating_add(metadata.len());
+                }
+            }
+        }
+        total
+    }
+
+    /// 작업(owner)이 끝날 때까지 캐시 정리에서 제외할 캐시 키 등록
+    pub fn exempt_from_pruning(&self, owner: &str, cache_keys: HashSet<String>) {
+        if let Ok(mut exemptions) = self.pruning_exemptions.lock() {
+            exemptions.insert(owner.to_string(), cache_keys);
+        }
+    }
+
+    pub fn end_pruning_exemption(&self, owner: &str) {
+        if let Ok(mut exemptions) = self.pruning_exemptions.lock() {
+            exemptions.remove(owner);
         }
     }
 
-    async fn prune_cache_if_needed(&self) -> Result<(), String> {
+    fn is_exempt_from_pruning(&self, cache_key: &str) -> bool {
+        self.pruning_exemptions
+            .lock()
+            .is_ok_and(|exemptions| exemptions.values().any(|keys| keys.contains(cache_key)))
+    }
+
+    /// 캐시 정리
+    /// 1. maxCacheAgeDays가 지난 영상 삭제
+    /// 2. 용량 초과 시 마지막 재생 시각이 오래된 영상부터 삭제
+    ///
+    /// 고정(pin)된 영상, 로컬에서 가져온 영상, 다운로드 중인 임시 파일,
+    /// 진행 중인 재생목록 다운로드의 영상은 삭제하지 않음
+    pub async fn prune_cache_if_needed(&self) -> Result<(), String> {
+        let config = self.read_config().await.unwrap_or_default();
         let max_bytes = self.max_cache_bytes().await;
-        if max_bytes == 0 {
+        let max_age_secs = config.maxCacheAgeDays as u64 * 24 * 60 * 60;
+        if max_bytes == 0 && max_age_secs == 0 {
             return Ok(());
         }
 
+        // 카탈로그에 없는 파일도 정리 대상에 포함되도록 먼저 동기화
+        self.library.reconcile(&self.videos_dir());
+
         let mut entries = tokio::fs::read_dir(self.videos_dir())
             .await
             .map_err(|e| e.to_string())?;
-        let mut files: Vec<(PathBuf, SystemTime, u64)> = Vec::new();
+        let mut files: Vec<(PathBuf, u64, u64)> = Vec::new();
         let mut total: u64 = 0;
 
         while let Some(entry) = entries.next_entry().await.map_err(|e| e.to_string())? {
             let metadata = entry.metadata().await.map_err(|e| e.to_string())?;
-            if metadata.is_file() {
-                let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
-                let size = metadata.len();
-                total = total.saturating_add(size);
-                files.push((entry.path(), modified, size));
+            if !metadata.is_file() {
+                continue;
             }
-        }
+            let size = metadata.len();
+            total = total.saturating_add(size);
 
-        if total <= max_bytes {
-            return Ok(());
+            let path = entry.path();
+            if is_incomplete_file(&path) {
+                continue;
+            }
+            let video_id = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or_default()
+                .to_string();
+            // 고정은 영상 단위라 오디오/병합/구간 파일도 함께 보호
+            let key_id = cache_key_id(&video_id);
+            if config
+                .pinnedVideos
+                .iter()
+                .any(|pinned| cache_key_id(pinned) == key_id)
+                || self.is_exempt_from_pruning(&video_id)
+            {
+                continue;
+            }
+            if self.is_imported_file(&path) {
+                continue;
+            }
+
+            let modified = metadata
+                .modified()
+                .unwrap_or(SystemTime::UNIX_EPOCH)
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            let last_used = self.library.last_used(&video_id).unwrap_or(modified);
+            files.push((path, last_used, size));
         }
 
-        // 오래된 파일부터 삭제
-        files.sort_by_key(|(_, modified, _)| *modified);
-        for (path, _, size) in files {
-            if total <= max_bytes {
-                break;
+        // 오래 재생되지 않은 파일부터 삭제
+        files.sort_by_key(|(_, last_used, _)| *last_used);
+        let now = crate::video_library::now_secs();
+        for (path, last_used, size) in files {
+            let expired = max_age_secs > 0 && now.saturating_sub(last_used) > max_age_secs;
+            let over_budget = max_bytes > 0 && total > max_bytes;
+            if !expired && !over_budget {
+                continue;
             }
             if tokio::fs::remove_file(&path).await.is_ok() {
                 total = total.saturating_sub(size);
+                if let Some(video_id) = path.file_stem().and_then(|s| s.to_str()) {
+                    self.library.remove(video_id);
+                }
+                tracing::info!(
+                    "Pruned cached video {:?} ({})",
+                    path,
+                    if expired { "expired" } else { "over budget" }
+                );
             }
         }
 
         Ok(())
     }
 
-    async fn max_cache_bytes(&self) -> u64 {
-        let config_path = self.data_dir.join("config.json");
-        if let Ok(content) = tokio::fs::read(&config_path).await {
-            if let Ok(cfg) = serde_json::from_slice::<AppConfig>(&content) {
-                return (cfg.maxCacheGB as u64) * 1024 * 1024 * 1024;
-            }
+    /// 로컬에서 가져온 캐시 파일인지 (다시 받을 수 없으므로 정리/비우기에서 제외)
+    pub fn is_imported_file(&self, path: &Path) -> bool {
+        let Some(key) = path.file_stem().and_then(|s| s.to_str()) else {
+            return false;
+        };
+        VideoSource::from_key_id(cache_key_id(key)).0 == VideoSource::Local
+            || self.library.get(key).is_some_and(|entry| entry.imported)
+    }
+
+    /// 캐시 용량 한도 (0이면 제한 없음)
+    pub async fn max_cache_bytes(&self) -> u64 {
+        if let Some(cfg) = self.read_config().await {
+            return (cfg.maxCacheGB as u64) * 1024 * 1024 * 1024;
         }
 
         // 기본값 10GB
         10 * 1024 * 1024 * 1024
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn safe_cache_keys() {
+        for key in [
+            "abc123def45",
+            "-bc123def45",
+            "abc123def45.audio",
+            "abc123def45.muxed.clip-30000-210000",
+            "niconico~sm9",
+            "url~0123456789abcdef.mp4",
+        ] {
+            assert!(is_safe_cache_key(key), "{} should be allowed", key);
+        }
+        assert!(is_safe_cache_key(&"a".repeat(128)));
+    }
+
+    #[test]
+    fn unsafe_cache_keys() {
+        for key in [
+            "",
+            "..",
+            "../secret",
+            "abc..mp4",
+            "abc/../../secret",
+            ".hidden",
+            ".",
+            "sub/abc123def45.mp4",
+            "/etc/passwd",
+            "sub\\abc123def45.mp4",
+            "..\\secret",
+            "%2e%2e",
+            "%2e%2e%2fsecret",
+            "..%2fsecret",
+            "abc%2fdef",
+            "abc def",
+            "abc\0def",
+        ] {
+            assert!(!is_safe_cache_key(key), "{:?} should be rejected", key);
+        }
+        assert!(!is_safe_cache_key(&"a".repeat(129)));
+    }
+
+    #[test]
+    fn cache_key_round_trip() {
+        for source in [
+            VideoSource::Youtube,
+            VideoSource::Bilibili,
+            VideoSource::Niconico,
+            VideoSource::Vimeo,
+            VideoSource::Local,
+        ] {
+            let request =
+                DownloadRequest::from_cache_key(&format!("{}.audio", source.key_id("id123")));
+            assert_eq!(request.source, source);
+            assert_eq!(request.video_id, "id123");
+            assert_eq!(request.mode, DownloadMode::Audio);
+            assert_eq!(cache_key_id(&request.cache_key()), source.key_id("id123"));
+        }
+    }
+}
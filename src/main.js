@@ -67,7 +67,7 @@ const i18n = {
     'api.lyricsRequest': 'Returns full lyrics of playing track',
     'api.nowLyricsRequest': 'Returns current lyric at playback position',
 
-    'confirm.clearCache': 'Delete all cached videos?',
+    'confirm.clearCache': 'Delete all downloaded videos? Imported videos are kept.',
     'unknown': 'Unknown'
   },
   ko: {
@@ -132,7 +132,7 @@ const i18n = {
     'api.lyricsRequest': '재생 중인 트랙의 전체 가사 반환',
     'api.nowLyricsRequest': '현재 재생 위치의 가사 반환',
 
-    'confirm.clearCache': '모든 캐시된 영상을 삭제하시겠습니까?',
+    'confirm.clearCache': '다운로드한 영상을 모두 삭제하시겠습니까? 가져온 영상은 유지됩니다.',
     'unknown': '알 수 없음'
   }
 };